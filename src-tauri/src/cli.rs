@@ -0,0 +1,205 @@
+// 命令行入口：不启动界面，直接在终端里做维护操作
+use crate::curve::CurveState;
+use crate::filter::FilterState;
+use crate::gamma::{recovery, GammaState};
+use crate::presets::PresetState;
+use crate::profile;
+use crate::settings::{self, Settings, SettingsState};
+use crate::solar::SolarState;
+use std::path::{Path, PathBuf};
+
+// 与 tauri.conf.json 中的 identifier 保持一致
+const APP_IDENTIFIER: &str = "com.admin.huyanba";
+
+/// 与 Tauri 的 app_config_dir 取同一位置，界面未启动时也能找到配置
+fn config_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|home| {
+            PathBuf::from(home)
+                .join("Library")
+                .join("Application Support")
+        })
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    base.map(|dir| dir.join(APP_IDENTIFIER))
+}
+
+/// 识别到维护参数时执行并返回退出码，否则返回 None 继续启动界面
+pub fn run(args: &[String]) -> Option<i32> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let code = match arg.as_str() {
+            "--restore-gamma" => restore_gamma(),
+            "--reset-gamma" => reset_gamma(),
+            "--reset-settings" => reset_settings(),
+            "--export-settings" | "--import-settings" => {
+                let Some(path) = args.next() else {
+                    eprintln!("{} 需要一个文件路径（.json 或 .toml）", arg);
+                    return Some(2);
+                };
+                if arg == "--export-settings" {
+                    export_settings(Path::new(path))
+                } else {
+                    import_settings(Path::new(path))
+                }
+            }
+            _ => continue,
+        };
+        return Some(code);
+    }
+    None
+}
+
+/// 配置目录下与界面共用的各项状态
+struct Store {
+    dir: PathBuf,
+    settings: SettingsState,
+    presets: PresetState,
+    solar: SolarState,
+    curve: CurveState,
+    filter: FilterState,
+}
+
+impl Store {
+    fn stores(&self) -> profile::Stores<'_> {
+        profile::Stores {
+            settings: &self.settings,
+            presets: &self.presets,
+            solar: &self.solar,
+            curve: &self.curve,
+            filter: &self.filter,
+        }
+    }
+}
+
+/// 打开配置目录下的各项设置；`strict` 为 false 时设置文件损坏也继续，后续保存会覆盖它
+fn open_store(strict: bool) -> Result<Store, String> {
+    let dir = config_dir().ok_or("无法定位配置目录")?;
+    std::fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+    let settings = SettingsState::default();
+    if let Err(err) = settings.load(dir.join(settings::SETTINGS_FILE)) {
+        if strict {
+            return Err(err);
+        }
+    }
+    let presets = PresetState::default();
+    presets.load(&dir.join("presets.json"));
+    let solar = SolarState::default();
+    solar.load(&dir.join("solar_schedule.json"));
+    let curve = CurveState::default();
+    curve.load(&dir.join("color_curve.json"));
+    let filter = FilterState::default();
+    filter.load_overrides(&dir.join("gamma_outputs.json"));
+    Ok(Store {
+        dir,
+        settings,
+        presets,
+        solar,
+        curve,
+        filter,
+    })
+}
+
+fn export_settings(path: &Path) -> i32 {
+    let result =
+        open_store(true).and_then(|store| profile::export_file(path, &store.stores().bundle()));
+    match result {
+        Ok(()) => {
+            println!("已导出配置到 {}", path.display());
+            0
+        }
+        Err(err) => {
+            eprintln!("导出配置失败: {}", err);
+            1
+        }
+    }
+}
+
+fn import_settings(path: &Path) -> i32 {
+    let bundle = match profile::import_file(path) {
+        Ok(bundle) => bundle,
+        Err(err) => {
+            eprintln!("配置文件校验失败:\n{}", err);
+            return 1;
+        }
+    };
+    let result = open_store(false).and_then(|store| {
+        store.stores().apply(&store.dir, bundle, |next| {
+            store.settings.replace(next.clone())?;
+            store.settings.save()?;
+            Ok(next)
+        })
+    });
+    match result {
+        Ok(_) => {
+            println!("已导入配置，正在运行的护眼吧需重启后生效");
+            0
+        }
+        Err(err) => {
+            eprintln!("导入配置失败: {}", err);
+            1
+        }
+    }
+}
+
+fn reset_settings() -> i32 {
+    let result = open_store(false).and_then(|store| {
+        store.settings.replace(Settings::default())?;
+        store.settings.save()
+    });
+    match result {
+        Ok(()) => {
+            println!("设置已恢复默认，自定义预设保留");
+            0
+        }
+        Err(err) => {
+            eprintln!("恢复默认设置失败: {}", err);
+            1
+        }
+    }
+}
+
+/// `--restore-gamma`：按崩溃标记还原屏幕，返回进程退出码
+fn restore_gamma() -> i32 {
+    let Some(dir) = config_dir() else {
+        eprintln!("无法定位配置目录");
+        return 1;
+    };
+    let gamma = GammaState::default();
+    gamma.set_marker_path(dir.join(recovery::MARKER_FILE));
+    match gamma.recover_from_marker() {
+        Ok(Some(count)) => {
+            println!("已还原 {} 个显示输出的 gamma", count);
+            0
+        }
+        Ok(None) => {
+            // 不回退到线性重置，否则会冲掉 ICC 或显卡驱动的校色曲线
+            println!("未发现崩溃标记，无需还原；确需写回线性曲线请用 --reset-gamma");
+            0
+        }
+        Err(err) => {
+            eprintln!("还原 gamma 失败: {}", err);
+            1
+        }
+    }
+}
+
+/// `--reset-gamma`：不看崩溃标记，所有输出强制写回线性曲线，校色曲线也会被清掉
+fn reset_gamma() -> i32 {
+    match GammaState::default().reset() {
+        Ok(()) => {
+            println!("已将 gamma 重置为线性曲线");
+            0
+        }
+        Err(err) => {
+            eprintln!("重置 gamma 失败: {}", err);
+            1
+        }
+    }
+}
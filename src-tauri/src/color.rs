@@ -0,0 +1,289 @@
+// 色温 → 白点换算：Helland 拟合、CIE 1931 黑体轨迹、Redshift 风格查表三种算法
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+pub type Rgb = (f64, f64, f64);
+
+/// 白点归一化的参考色温，此时三通道均为 1
+const REFERENCE_TEMP: f64 = 6500.0;
+const MIN_TEMP: f64 = 1000.0;
+const MAX_TEMP: f64 = 25000.0;
+const TABLE_STEP: f64 = 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ColorAlgorithm {
+    /// Tanner Helland 曲线拟合，配合偏绿修正，保持原有观感
+    #[default]
+    Helland,
+    /// 普朗克黑体辐射积分，线性光下混合后再做 sRGB 编码
+    Planckian,
+    /// 按 100K 步长查表插值，直接缩放 gamma 曲线（与 Redshift 相同）
+    RedshiftTable,
+}
+
+fn clamp(value: f64, min: f64, max: f64) -> f64 {
+    if value < min {
+        min
+    } else if value > max {
+        max
+    } else {
+        value
+    }
+}
+
+fn srgb_encode(linear: f64) -> f64 {
+    let linear = clamp(linear, 0.0, 1.0);
+    if linear <= 0.0031308 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+pub fn helland_rgb(temp: f64) -> Rgb {
+    let temp = clamp(temp, MIN_TEMP, 40000.0) / 100.0;
+    let (mut r, mut g, mut b);
+    if temp <= 66.0 {
+        r = 255.0;
+        g = 99.4708025861 * temp.ln() - 161.1195681661;
+        b = if temp <= 19.0 {
+            0.0
+        } else {
+            138.5177312231 * (temp - 10.0).ln() - 305.0447927307
+        };
+    } else {
+        r = 329.698727446 * (temp - 60.0).powf(-0.1332047592);
+        g = 288.1221695283 * (temp - 60.0).powf(-0.0755148492);
+        b = 255.0;
+    }
+
+    r = clamp(r, 0.0, 255.0);
+    g = clamp(g, 0.0, 255.0);
+    b = clamp(b, 0.0, 255.0);
+    (r / 255.0, g / 255.0, b / 255.0)
+}
+
+/// 分段高斯，左右两侧宽度不同
+fn lobe(wavelength: f64, mu: f64, sigma_low: f64, sigma_high: f64) -> f64 {
+    let sigma = if wavelength < mu {
+        sigma_low
+    } else {
+        sigma_high
+    };
+    let t = (wavelength - mu) / sigma;
+    (-0.5 * t * t).exp()
+}
+
+/// CIE 1931 2° 标准观察者配色函数（Wyman-Sloan-Shirley 多峰拟合）
+fn cie_cmf(wavelength: f64) -> (f64, f64, f64) {
+    let x = 1.056 * lobe(wavelength, 599.8, 37.9, 31.0)
+        + 0.362 * lobe(wavelength, 442.0, 16.0, 26.7)
+        - 0.065 * lobe(wavelength, 501.1, 20.4, 26.2);
+    let y =
+        0.821 * lobe(wavelength, 568.8, 46.9, 40.5) + 0.286 * lobe(wavelength, 530.9, 16.3, 31.1);
+    let z =
+        1.217 * lobe(wavelength, 437.0, 11.8, 36.0) + 0.681 * lobe(wavelength, 459.0, 26.0, 13.8);
+    (x, y, z)
+}
+
+/// 普朗克定律的相对光谱辐射度，波长单位 nm
+fn planck(wavelength_nm: f64, temp: f64) -> f64 {
+    // 第二辐射常数 hc/k，单位 m·K
+    const C2: f64 = 1.438_776_877e-2;
+    let wavelength = wavelength_nm * 1e-9;
+    1.0 / (wavelength.powi(5) * ((C2 / (wavelength * temp)).exp() - 1.0))
+}
+
+fn blackbody_linear_srgb(temp: f64) -> Rgb {
+    let (mut x, mut y, mut z) = (0.0, 0.0, 0.0);
+    let mut wavelength = 380.0;
+    while wavelength <= 780.0 {
+        let power = planck(wavelength, temp);
+        let (cx, cy, cz) = cie_cmf(wavelength);
+        x += power * cx;
+        y += power * cy;
+        z += power * cz;
+        wavelength += 5.0;
+    }
+    (
+        3.2404542 * x - 1.5371385 * y - 0.4985314 * z,
+        -0.9692660 * x + 1.8760108 * y + 0.0415560 * z,
+        0.0556434 * x - 0.2040259 * y + 1.0572252 * z,
+    )
+}
+
+/// 黑体白点（线性光），以 6500K 为白、最亮通道为 1
+pub fn planckian_rgb(temp: f64) -> Rgb {
+    let temp = clamp(temp, MIN_TEMP, MAX_TEMP);
+    let (r, g, b) = blackbody_linear_srgb(temp);
+    let (ref_r, ref_g, ref_b) = blackbody_linear_srgb(REFERENCE_TEMP);
+    let (r, g, b) = (
+        (r / ref_r).max(0.0),
+        (g / ref_g).max(0.0),
+        (b / ref_b).max(0.0),
+    );
+    let max = r.max(g).max(b);
+    (r / max, g / max, b / max)
+}
+
+fn whitepoint_table() -> &'static [Rgb] {
+    static TABLE: OnceLock<Vec<Rgb>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let steps = ((MAX_TEMP - MIN_TEMP) / TABLE_STEP) as usize;
+        (0..=steps)
+            .map(|i| planckian_rgb(MIN_TEMP + i as f64 * TABLE_STEP))
+            .collect()
+    })
+}
+
+/// 查表并在相邻两档之间线性插值
+pub fn table_rgb(temp: f64) -> Rgb {
+    let table = whitepoint_table();
+    let position = (clamp(temp, MIN_TEMP, MAX_TEMP) - MIN_TEMP) / TABLE_STEP;
+    let index = (position.floor() as usize).min(table.len() - 2);
+    let t = position - index as f64;
+    let (low, high) = (table[index], table[index + 1]);
+    (
+        low.0 + (high.0 - low.0) * t,
+        low.1 + (high.1 - low.1) * t,
+        low.2 + (high.2 - low.2) * t,
+    )
+}
+
+impl ColorAlgorithm {
+    /// 按强度混合出 gamma 曲线（编码域）上的三通道倍率
+    pub fn multipliers(self, strength: f64, temp: f64) -> Rgb {
+        let factor = clamp(strength / 100.0, 0.0, 1.0);
+        let blend = |white: f64| (1.0 - factor) + factor * white;
+        match self {
+            ColorAlgorithm::Helland => {
+                let (r, g, b) = helland_rgb(temp);
+                // Greenish bias to avoid reddish tint and reduce blue light.
+                let green_boost = 0.08 * factor;
+                let red_cut = 0.18 * factor;
+                let blue_cut = 0.35 * factor;
+                (
+                    clamp(blend(r) * (1.0 - red_cut), 0.0, 1.0),
+                    clamp(blend(g) * (1.0 + green_boost), 0.0, 1.0),
+                    clamp(blend(b) * (1.0 - blue_cut), 0.0, 1.0),
+                )
+            }
+            ColorAlgorithm::Planckian => {
+                let (r, g, b) = planckian_rgb(temp);
+                (
+                    srgb_encode(blend(r)),
+                    srgb_encode(blend(g)),
+                    srgb_encode(blend(b)),
+                )
+            }
+            ColorAlgorithm::RedshiftTable => {
+                let (r, g, b) = table_rgb(temp);
+                (blend(r), blend(g), blend(b))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALGORITHMS: [ColorAlgorithm; 3] = [
+        ColorAlgorithm::Helland,
+        ColorAlgorithm::Planckian,
+        ColorAlgorithm::RedshiftTable,
+    ];
+
+    fn close(a: Rgb, b: Rgb, tolerance: f64) -> bool {
+        (a.0 - b.0).abs() < tolerance
+            && (a.1 - b.1).abs() < tolerance
+            && (a.2 - b.2).abs() < tolerance
+    }
+
+    #[test]
+    fn helland_is_clamped_to_unit_range() {
+        for temp in [0.0, 1000.0, 3400.0, 6500.0, 12000.0, 100000.0] {
+            let (r, g, b) = helland_rgb(temp);
+            for value in [r, g, b] {
+                assert!((0.0..=1.0).contains(&value), "{}K -> {}", temp, value);
+            }
+        }
+        assert_eq!(helland_rgb(1000.0).2, 0.0);
+    }
+
+    #[test]
+    fn srgb_encode_matches_reference_points() {
+        assert_eq!(srgb_encode(0.0), 0.0);
+        assert!((srgb_encode(1.0) - 1.0).abs() < 1e-9);
+        assert!((srgb_encode(0.18) - 0.4613).abs() < 1e-3);
+        assert!((srgb_encode(0.002) - 0.002 * 12.92).abs() < 1e-12);
+    }
+
+    #[test]
+    fn planckian_is_white_at_reference() {
+        assert!(close(planckian_rgb(REFERENCE_TEMP), (1.0, 1.0, 1.0), 1e-9));
+    }
+
+    #[test]
+    fn planckian_warms_as_temperature_drops() {
+        let mut previous = planckian_rgb(REFERENCE_TEMP);
+        for temp in [5500.0, 4500.0, 3500.0, 2500.0, 1500.0] {
+            let (r, g, b) = planckian_rgb(temp);
+            assert_eq!(r, 1.0, "{}K", temp);
+            assert!(g > b, "{}K", temp);
+            assert!(g < previous.1 && b < previous.2, "{}K", temp);
+            previous = (r, g, b);
+        }
+    }
+
+    #[test]
+    fn planckian_cools_above_reference() {
+        let (r, g, b) = planckian_rgb(10000.0);
+        assert_eq!(b, 1.0);
+        assert!(r < g && g < b);
+    }
+
+    #[test]
+    fn table_matches_planckian_on_grid_and_interpolates() {
+        for temp in [1000.0, 3400.0, 6500.0, 25000.0] {
+            assert!(
+                close(table_rgb(temp), planckian_rgb(temp), 1e-9),
+                "{}K",
+                temp
+            );
+        }
+        let low = table_rgb(4700.0);
+        let high = table_rgb(4800.0);
+        let mid = table_rgb(4750.0);
+        assert!(close(
+            mid,
+            (
+                (low.0 + high.0) / 2.0,
+                (low.1 + high.1) / 2.0,
+                (low.2 + high.2) / 2.0
+            ),
+            1e-9
+        ));
+    }
+
+    #[test]
+    fn zero_strength_is_neutral_for_every_algorithm() {
+        for algorithm in ALGORITHMS {
+            assert!(
+                close(algorithm.multipliers(0.0, 2700.0), (1.0, 1.0, 1.0), 1e-9),
+                "{:?}",
+                algorithm
+            );
+        }
+    }
+
+    #[test]
+    fn warm_temperatures_cut_blue_the_most() {
+        for algorithm in ALGORITHMS {
+            let (r, _, b) = algorithm.multipliers(60.0, 3400.0);
+            assert!(b < r, "{:?}", algorithm);
+            assert!(b > 0.0, "{:?}", algorithm);
+        }
+    }
+}
@@ -0,0 +1,397 @@
+// 自定义时间曲线：按一天中的关键帧（时刻 → 色温/强度）线性插值，跨午夜首尾相接
+use crate::filter::FilterSetting;
+use chrono::{Local, Timelike};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const MINUTES_PER_DAY: f64 = 1440.0;
+const TICK_SECS: u64 = 60;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Keyframe {
+    pub id: u32,
+    /// 24 小时制 "HH:MM"
+    pub time: String,
+    pub color_temp: f64,
+    pub strength: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyframeInput {
+    pub time: String,
+    pub color_temp: f64,
+    pub strength: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorCurve {
+    pub enabled: bool,
+    pub keyframes: Vec<Keyframe>,
+}
+
+impl Default for ColorCurve {
+    fn default() -> Self {
+        let keyframe = |id, time: &str, color_temp, strength| Keyframe {
+            id,
+            time: time.to_string(),
+            color_temp,
+            strength,
+        };
+        Self {
+            enabled: false,
+            keyframes: vec![
+                keyframe(1, "07:00", 6500.0, 0.0),
+                keyframe(2, "20:00", 4500.0, 40.0),
+                keyframe(3, "23:00", 3200.0, 60.0),
+            ],
+        }
+    }
+}
+
+pub fn parse_time(time: &str) -> Result<u32, String> {
+    let invalid = || format!("时间格式应为 HH:MM: {}", time);
+    let (hours, minutes) = time.trim().split_once(':').ok_or_else(invalid)?;
+    let hours: u32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u32 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 23 || minutes > 59 {
+        return Err(invalid());
+    }
+    Ok(hours * 60 + minutes)
+}
+
+impl KeyframeInput {
+    fn validate(&self) -> Result<u32, String> {
+        if !(1000.0..=10000.0).contains(&self.color_temp) {
+            return Err("色温需在 1000K 到 10000K 之间".into());
+        }
+        if !(0.0..=100.0).contains(&self.strength) {
+            return Err("强度需在 0 到 100 之间".into());
+        }
+        parse_time(&self.time)
+    }
+}
+
+impl ColorCurve {
+    /// 按时刻排序后的 (分钟, 关键帧)
+    fn sorted(&self) -> Vec<(u32, &Keyframe)> {
+        let mut frames: Vec<(u32, &Keyframe)> = self
+            .keyframes
+            .iter()
+            .filter_map(|frame| parse_time(&frame.time).ok().map(|minute| (minute, frame)))
+            .collect();
+        frames.sort_by_key(|(minute, _)| *minute);
+        frames
+    }
+
+    /// 一天中第 `minute` 分钟的设置；没有关键帧时返回 None
+    pub fn setting_at(&self, minute: f64) -> Option<FilterSetting> {
+        let frames = self.sorted();
+        let minute = minute.rem_euclid(MINUTES_PER_DAY);
+        let next_index = frames
+            .iter()
+            .position(|(start, _)| *start as f64 > minute)
+            .unwrap_or(0);
+        let prev_index = (next_index + frames.len()).checked_sub(1)? % frames.len();
+        let (prev_minute, prev) = frames[prev_index];
+        let (next_minute, next) = frames[next_index];
+        let span = (next_minute as f64 - prev_minute as f64).rem_euclid(MINUTES_PER_DAY);
+        let t = if span == 0.0 {
+            0.0
+        } else {
+            (minute - prev_minute as f64).rem_euclid(MINUTES_PER_DAY) / span
+        };
+        let mix = |from: f64, to: f64| from + (to - from) * t;
+        Some(FilterSetting {
+            filter_enabled: true,
+            strength: mix(prev.strength, next.strength).round(),
+            color_temp: (mix(prev.color_temp, next.color_temp) / 10.0).round() * 10.0,
+        })
+    }
+
+    /// 整条曲线一次替换（导入配置）前逐帧检查，返回 (下标, 错误)
+    pub fn keyframe_errors(&self) -> Vec<(usize, String)> {
+        let mut errors = Vec::new();
+        for (index, frame) in self.keyframes.iter().enumerate() {
+            let input = KeyframeInput {
+                time: frame.time.clone(),
+                color_temp: frame.color_temp,
+                strength: frame.strength,
+            };
+            let minute = match input.validate() {
+                Ok(minute) => minute,
+                Err(err) => {
+                    errors.push((index, err));
+                    continue;
+                }
+            };
+            let earlier = &self.keyframes[..index];
+            if earlier.iter().any(|other| other.id == frame.id) {
+                errors.push((index, format!("关键帧 id 重复: {}", frame.id)));
+            } else if earlier
+                .iter()
+                .any(|other| parse_time(&other.time).ok() == Some(minute))
+            {
+                errors.push((index, "该时刻已有关键帧".into()));
+            }
+        }
+        errors
+    }
+
+    fn ensure_unique_time(&self, minute: u32, except: Option<u32>) -> Result<(), String> {
+        let taken = self
+            .keyframes
+            .iter()
+            .any(|frame| Some(frame.id) != except && parse_time(&frame.time).ok() == Some(minute));
+        if taken {
+            return Err("该时刻已有关键帧".into());
+        }
+        Ok(())
+    }
+
+    pub fn add(&mut self, input: KeyframeInput) -> Result<u32, String> {
+        let minute = input.validate()?;
+        self.ensure_unique_time(minute, None)?;
+        let id = self
+            .keyframes
+            .iter()
+            .map(|frame| frame.id)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        self.keyframes.push(Keyframe {
+            id,
+            time: format!("{:02}:{:02}", minute / 60, minute % 60),
+            color_temp: input.color_temp,
+            strength: input.strength,
+        });
+        self.keyframes
+            .sort_by_key(|frame| parse_time(&frame.time).unwrap_or(0));
+        Ok(id)
+    }
+
+    pub fn update(&mut self, id: u32, input: KeyframeInput) -> Result<(), String> {
+        let minute = input.validate()?;
+        self.ensure_unique_time(minute, Some(id))?;
+        let frame = self
+            .keyframes
+            .iter_mut()
+            .find(|frame| frame.id == id)
+            .ok_or("关键帧不存在")?;
+        frame.time = format!("{:02}:{:02}", minute / 60, minute % 60);
+        frame.color_temp = input.color_temp;
+        frame.strength = input.strength;
+        self.keyframes
+            .sort_by_key(|frame| parse_time(&frame.time).unwrap_or(0));
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: u32) -> Result<(), String> {
+        let before = self.keyframes.len();
+        self.keyframes.retain(|frame| frame.id != id);
+        if self.keyframes.len() == before {
+            return Err("关键帧不存在".into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct CurveState {
+    curve: Mutex<ColorCurve>,
+    last_applied: Mutex<Option<FilterSetting>>,
+}
+
+impl CurveState {
+    pub fn curve(&self) -> ColorCurve {
+        self.curve
+            .lock()
+            .map(|value| value.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.curve
+            .lock()
+            .map(|value| value.enabled)
+            .unwrap_or(false)
+    }
+
+    /// 修改曲线并让下一次调度强制重新写入
+    pub fn update<T>(
+        &self,
+        change: impl FnOnce(&mut ColorCurve) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut curve = self.curve.lock().map_err(|_| "曲线设置被占用")?;
+        let result = change(&mut curve)?;
+        if let Ok(mut last) = self.last_applied.lock() {
+            *last = None;
+        }
+        Ok(result)
+    }
+
+    /// 让下一次调度强制重新写入
+    pub fn forget_applied(&self) {
+        if let Ok(mut last) = self.last_applied.lock() {
+            *last = None;
+        }
+    }
+
+    pub fn load(&self, path: &Path) {
+        let Ok(data) = fs::read_to_string(path) else {
+            return;
+        };
+        let Ok(saved) = serde_json::from_str::<ColorCurve>(&data) else {
+            return;
+        };
+        let _ = self.update(|curve| {
+            *curve = saved;
+            Ok(())
+        });
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let data = serde_json::to_string_pretty(&self.curve()).map_err(|err| err.to_string())?;
+        crate::settings::write_atomic(path, data.as_bytes())
+    }
+
+    fn next_setting(&self, minute: f64) -> Option<FilterSetting> {
+        let curve = self.curve();
+        if !curve.enabled {
+            return None;
+        }
+        let setting = curve.setting_at(minute)?;
+        let mut last = self.last_applied.lock().ok()?;
+        if *last == Some(setting) {
+            return None;
+        }
+        *last = Some(setting);
+        Some(setting)
+    }
+}
+
+fn local_minute() -> f64 {
+    let now = Local::now();
+    now.hour() as f64 * 60.0 + now.minute() as f64 + now.second() as f64 / 60.0
+}
+
+/// 与 set_gamma 同一路径写入，并通知主界面同步滑块
+pub fn tick(app: &AppHandle) -> Result<(), String> {
+    let Some(setting) = app.state::<CurveState>().next_setting(local_minute()) else {
+        return Ok(());
+    };
+    crate::apply_global_filter(app, setting)?;
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit("curve-filter", setting);
+    }
+    Ok(())
+}
+
+pub fn run_curve_schedule(app: AppHandle) {
+    loop {
+        if let Err(err) = tick(&app) {
+            crate::append_app_log(&app, &format!("时间曲线应用失败: {}", err));
+        }
+        std::thread::sleep(Duration::from_secs(TICK_SECS));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(time: &str, color_temp: f64, strength: f64) -> KeyframeInput {
+        KeyframeInput {
+            time: time.to_string(),
+            color_temp,
+            strength,
+        }
+    }
+
+    fn minute(time: &str) -> f64 {
+        parse_time(time).unwrap() as f64
+    }
+
+    #[test]
+    fn parses_and_rejects_times() {
+        assert_eq!(parse_time("07:05"), Ok(425));
+        assert_eq!(parse_time("0:00"), Ok(0));
+        assert!(parse_time("24:00").is_err());
+        assert!(parse_time("12:60").is_err());
+        assert!(parse_time("noon").is_err());
+    }
+
+    #[test]
+    fn interpolates_between_keyframes() {
+        let curve = ColorCurve::default();
+        let at_keyframe = curve.setting_at(minute("20:00")).unwrap();
+        assert_eq!(
+            (at_keyframe.color_temp, at_keyframe.strength),
+            (4500.0, 40.0)
+        );
+        let halfway = curve.setting_at(minute("21:30")).unwrap();
+        assert_eq!((halfway.color_temp, halfway.strength), (3850.0, 50.0));
+    }
+
+    #[test]
+    fn wraps_around_midnight() {
+        let curve = ColorCurve::default();
+        // 23:00 → 次日 07:00 共 8 小时，凌晨 3 点正好一半
+        let night = curve.setting_at(minute("03:00")).unwrap();
+        assert_eq!((night.color_temp, night.strength), (4850.0, 30.0));
+        let late = curve.setting_at(minute("23:30")).unwrap();
+        assert!(late.color_temp > 3200.0 && late.color_temp < night.color_temp);
+    }
+
+    #[test]
+    fn single_or_no_keyframe() {
+        let mut curve = ColorCurve {
+            enabled: true,
+            keyframes: Vec::new(),
+        };
+        assert!(curve.setting_at(600.0).is_none());
+        curve.add(input("12:00", 5000.0, 20.0)).unwrap();
+        for time in ["00:00", "12:00", "18:30"] {
+            let setting = curve.setting_at(minute(time)).unwrap();
+            assert_eq!((setting.color_temp, setting.strength), (5000.0, 20.0));
+        }
+    }
+
+    #[test]
+    fn crud_keeps_keyframes_sorted_and_unique() {
+        let mut curve = ColorCurve::default();
+        let id = curve.add(input("9:30", 6000.0, 10.0)).unwrap();
+        let times: Vec<&str> = curve.keyframes.iter().map(|f| f.time.as_str()).collect();
+        assert_eq!(times, ["07:00", "09:30", "20:00", "23:00"]);
+        assert!(curve.add(input("20:00", 4000.0, 10.0)).is_err());
+        assert!(curve.add(input("10:00", 500.0, 10.0)).is_err());
+
+        curve.update(id, input("21:00", 4000.0, 45.0)).unwrap();
+        assert_eq!(curve.keyframes[2].id, id);
+        assert!(curve.update(id, input("07:00", 4000.0, 45.0)).is_err());
+
+        curve.remove(id).unwrap();
+        assert_eq!(curve.keyframes.len(), 3);
+        assert!(curve.remove(id).is_err());
+    }
+
+    #[test]
+    fn state_applies_only_when_enabled_and_changed() {
+        let state = CurveState::default();
+        assert!(state.next_setting(600.0).is_none());
+        state
+            .update(|curve| {
+                curve.enabled = true;
+                Ok(())
+            })
+            .unwrap();
+        assert!(state.next_setting(600.0).is_some());
+        assert!(state.next_setting(600.0).is_none());
+    }
+}
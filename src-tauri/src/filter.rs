@@ -0,0 +1,256 @@
+// 过滤蓝光设置：全局色温/强度 + 按显示器覆盖，统一换算成 gamma 倍率
+use crate::color::ColorAlgorithm;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterSetting {
+    pub filter_enabled: bool,
+    pub strength: f64,
+    pub color_temp: f64,
+}
+
+impl Default for FilterSetting {
+    fn default() -> Self {
+        Self {
+            filter_enabled: false,
+            strength: 30.0,
+            color_temp: 4700.0,
+        }
+    }
+}
+
+impl FilterSetting {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=100.0).contains(&self.strength) {
+            return Err("强度需在 0 到 100 之间".into());
+        }
+        if !(1000.0..=10000.0).contains(&self.color_temp) {
+            return Err("色温需在 1000K 到 10000K 之间".into());
+        }
+        Ok(())
+    }
+
+    /// 关闭时返回 None，由后端还原该输出
+    pub fn multipliers(&self, algorithm: ColorAlgorithm) -> Option<(f64, f64, f64)> {
+        if !self.filter_enabled {
+            return None;
+        }
+        Some(algorithm.multipliers(self.strength, self.color_temp))
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputOverrides {
+    outputs: HashMap<String, FilterSetting>,
+}
+
+#[derive(Default)]
+pub struct FilterState {
+    global: Mutex<FilterSetting>,
+    overrides: Mutex<HashMap<String, FilterSetting>>,
+    algorithm: Mutex<ColorAlgorithm>,
+}
+
+impl FilterState {
+    pub fn global(&self) -> FilterSetting {
+        self.global.lock().map(|value| *value).unwrap_or_default()
+    }
+
+    pub fn set_global(&self, setting: FilterSetting) {
+        if let Ok(mut global) = self.global.lock() {
+            *global = setting;
+        }
+    }
+
+    pub fn algorithm(&self) -> ColorAlgorithm {
+        self.algorithm
+            .lock()
+            .map(|value| *value)
+            .unwrap_or_default()
+    }
+
+    pub fn set_algorithm(&self, algorithm: ColorAlgorithm) {
+        if let Ok(mut current) = self.algorithm.lock() {
+            *current = algorithm;
+        }
+    }
+
+    pub fn overrides(&self) -> HashMap<String, FilterSetting> {
+        self.overrides
+            .lock()
+            .map(|value| value.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_output(&self, output: &str, setting: FilterSetting) {
+        if let Ok(mut overrides) = self.overrides.lock() {
+            overrides.insert(output.to_string(), setting);
+        }
+    }
+
+    pub fn clear_output(&self, output: &str) {
+        if let Ok(mut overrides) = self.overrides.lock() {
+            overrides.remove(output);
+        }
+    }
+
+    pub fn replace_overrides(&self, outputs: HashMap<String, FilterSetting>) {
+        if let Ok(mut overrides) = self.overrides.lock() {
+            *overrides = outputs;
+        }
+    }
+
+    /// 未单独设置的显示器使用全局设置
+    pub fn multipliers_for(&self, output: &str) -> Option<(f64, f64, f64)> {
+        self.overrides()
+            .get(output)
+            .copied()
+            .unwrap_or_else(|| self.global())
+            .multipliers(self.algorithm())
+    }
+
+    pub fn load_overrides(&self, path: &Path) {
+        let Ok(data) = fs::read_to_string(path) else {
+            return;
+        };
+        let saved: OutputOverrides = serde_json::from_str(&data).unwrap_or_default();
+        if let Ok(mut overrides) = self.overrides.lock() {
+            *overrides = saved.outputs;
+        }
+    }
+
+    pub fn save_overrides(&self, path: &Path) -> Result<(), String> {
+        let saved = OutputOverrides {
+            outputs: self.overrides(),
+        };
+        let data = serde_json::to_string_pretty(&saved).map_err(|err| err.to_string())?;
+        crate::settings::write_atomic(path, data.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::helland_rgb;
+    use crate::gamma::{mock::MockBackend, GammaRamp, GammaState, DEFAULT_RAMP_SIZE};
+
+    fn setting(filter_enabled: bool, strength: f64, color_temp: f64) -> FilterSetting {
+        FilterSetting {
+            filter_enabled,
+            strength,
+            color_temp,
+        }
+    }
+
+    fn ramp_after(filter_enabled: bool, strength: f64, color_temp: f64) -> GammaRamp {
+        let mock = MockBackend::new(&["DISPLAY1"]);
+        let gamma = GammaState::with_backend(Box::new(mock.clone()));
+        let filter = FilterState::default();
+        filter.set_global(setting(filter_enabled, strength, color_temp));
+        gamma
+            .apply_with(|output| filter.multipliers_for(output))
+            .unwrap();
+        mock.last_ramp("DISPLAY1").expect("应写入 gamma 曲线")
+    }
+
+    fn is_monotonic(channel: &[u16]) -> bool {
+        channel.windows(2).all(|pair| pair[0] <= pair[1])
+    }
+
+    #[test]
+    fn ramps_are_monotonic() {
+        for temp in [2000.0, 3400.0, 4700.0, 6500.0] {
+            for strength in [0.0, 30.0, 60.0, 100.0] {
+                let ramp = ramp_after(true, strength, temp);
+                assert_eq!(ramp.len(), DEFAULT_RAMP_SIZE);
+                assert!(is_monotonic(&ramp.red), "{}K {}%", temp, strength);
+                assert!(is_monotonic(&ramp.green), "{}K {}%", temp, strength);
+                assert!(is_monotonic(&ramp.blue), "{}K {}%", temp, strength);
+            }
+        }
+    }
+
+    #[test]
+    fn zero_strength_writes_neutral_ramp() {
+        assert_eq!(
+            ramp_after(true, 0.0, 3400.0),
+            GammaRamp::linear(DEFAULT_RAMP_SIZE)
+        );
+    }
+
+    #[test]
+    fn disabled_filter_writes_neutral_ramp() {
+        assert_eq!(
+            ramp_after(false, 80.0, 2000.0),
+            GammaRamp::linear(DEFAULT_RAMP_SIZE)
+        );
+    }
+
+    #[test]
+    fn green_bias_keeps_green_above_red_above_blue() {
+        for (temp, strength) in [(4700.0, 30.0), (5200.0, 50.0), (6000.0, 35.0)] {
+            let ramp = ramp_after(true, strength, temp);
+            let top = DEFAULT_RAMP_SIZE - 1;
+            assert!(ramp.green[top] > ramp.red[top], "{}K {}%", temp, strength);
+            assert!(ramp.red[top] > ramp.blue[top], "{}K {}%", temp, strength);
+        }
+    }
+
+    #[test]
+    fn green_bias_cuts_red_and_blue_more_than_plain_blend() {
+        let (r, g, b) = helland_rgb(4700.0);
+        let factor = 0.5;
+        let (mult_r, mult_g, mult_b) = ColorAlgorithm::Helland.multipliers(50.0, 4700.0);
+        assert!(mult_r < (1.0 - factor) + factor * r);
+        assert!(mult_g > (1.0 - factor) + factor * g);
+        assert!(mult_b < (1.0 - factor) + factor * b);
+    }
+
+    #[test]
+    fn output_override_only_affects_that_output() {
+        let mock = MockBackend::new(&["DISPLAY1", "DISPLAY2"]);
+        let gamma = GammaState::with_backend(Box::new(mock.clone()));
+        let filter = FilterState::default();
+        filter.set_global(setting(true, 30.0, 4700.0));
+        filter.set_output("DISPLAY2", setting(true, 80.0, 3000.0));
+        gamma
+            .apply_with(|output| filter.multipliers_for(output))
+            .unwrap();
+
+        let first = mock.last_ramp("DISPLAY1").unwrap();
+        let second = mock.last_ramp("DISPLAY2").unwrap();
+        assert!(second.blue[255] < first.blue[255]);
+
+        filter.clear_output("DISPLAY2");
+        gamma
+            .apply_with(|output| filter.multipliers_for(output))
+            .unwrap();
+        assert_eq!(mock.last_ramp("DISPLAY2"), mock.last_ramp("DISPLAY1"));
+    }
+
+    #[test]
+    fn disabled_override_resets_only_that_output() {
+        let mock = MockBackend::new(&["DISPLAY1", "DISPLAY2"]);
+        let gamma = GammaState::with_backend(Box::new(mock.clone()));
+        let filter = FilterState::default();
+        filter.set_global(setting(true, 50.0, 4000.0));
+        filter.set_output("DISPLAY1", setting(false, 50.0, 4000.0));
+        gamma
+            .apply_with(|output| filter.multipliers_for(output))
+            .unwrap();
+        assert_eq!(
+            mock.last_ramp("DISPLAY1"),
+            Some(GammaRamp::linear(DEFAULT_RAMP_SIZE))
+        );
+        assert_ne!(
+            mock.last_ramp("DISPLAY2"),
+            Some(GammaRamp::linear(DEFAULT_RAMP_SIZE))
+        );
+    }
+}
@@ -0,0 +1,31 @@
+// 测试用前台窗口：由测试直接设定
+use super::{Foreground, ForegroundProvider};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+pub struct MockForeground {
+    window: Arc<Mutex<Option<Foreground>>>,
+}
+
+impl MockForeground {
+    pub fn set(&self, process: &str, fullscreen: bool) {
+        *self.window.lock().unwrap() = Some(Foreground {
+            process: process.into(),
+            fullscreen,
+        });
+    }
+
+    pub fn clear(&self) {
+        *self.window.lock().unwrap() = None;
+    }
+}
+
+impl ForegroundProvider for MockForeground {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn foreground(&mut self) -> Option<Foreground> {
+        self.window.lock().unwrap().clone()
+    }
+}
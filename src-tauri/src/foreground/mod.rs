@@ -0,0 +1,48 @@
+// 前台窗口检测：判断当前是否有全屏窗口、前台是哪个进程，用于演示或会议时推迟休息
+#[cfg(test)]
+pub mod mock;
+#[cfg(windows)]
+mod win32;
+#[cfg(all(target_os = "linux", feature = "x11"))]
+mod x11;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Foreground {
+    /// 进程名，不含路径与扩展名
+    pub process: String,
+    /// 窗口是否铺满所在显示器
+    pub fullscreen: bool,
+}
+
+pub trait ForegroundProvider: Send {
+    fn name(&self) -> &'static str;
+    /// 查询失败或平台不支持时返回 None
+    fn foreground(&mut self) -> Option<Foreground>;
+}
+
+struct UnsupportedProvider;
+
+impl ForegroundProvider for UnsupportedProvider {
+    fn name(&self) -> &'static str {
+        "unsupported"
+    }
+
+    fn foreground(&mut self) -> Option<Foreground> {
+        None
+    }
+}
+
+#[cfg(windows)]
+pub fn default_provider() -> Box<dyn ForegroundProvider> {
+    Box::new(win32::Win32Foreground)
+}
+
+#[cfg(not(windows))]
+pub fn default_provider() -> Box<dyn ForegroundProvider> {
+    // Wayland 没有查询前台窗口的通用协议，只能经 XWayland 看到 X 客户端
+    #[cfg(all(target_os = "linux", feature = "x11"))]
+    if let Ok(provider) = x11::X11Foreground::open() {
+        return Box::new(provider);
+    }
+    Box::new(UnsupportedProvider)
+}
@@ -0,0 +1,85 @@
+// Win32：前台窗口矩形盖住所在显示器即视为全屏，进程名取可执行文件名
+use super::{Foreground, ForegroundProvider};
+use std::path::Path;
+use windows::core::PWSTR;
+use windows::Win32::Foundation::{CloseHandle, HWND, RECT};
+use windows::Win32::Graphics::Gdi::{
+    GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONEAREST,
+};
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetDesktopWindow, GetForegroundWindow, GetShellWindow, GetWindowRect, GetWindowThreadProcessId,
+};
+
+pub struct Win32Foreground;
+
+unsafe fn process_name(window: HWND) -> String {
+    let mut pid = 0u32;
+    GetWindowThreadProcessId(window, Some(&mut pid));
+    let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) else {
+        return String::new();
+    };
+    let mut buffer = [0u16; 1024];
+    let mut size = buffer.len() as u32;
+    let queried = QueryFullProcessImageNameW(
+        process,
+        PROCESS_NAME_WIN32,
+        PWSTR(buffer.as_mut_ptr()),
+        &mut size,
+    );
+    let _ = CloseHandle(process);
+    if queried.is_err() {
+        return String::new();
+    }
+    let path = String::from_utf16_lossy(&buffer[..size as usize]);
+    Path::new(&path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+unsafe fn covers_monitor(window: HWND) -> bool {
+    let mut rect = RECT::default();
+    if GetWindowRect(window, &mut rect).is_err() {
+        return false;
+    }
+    let monitor = MonitorFromWindow(window, MONITOR_DEFAULTTONEAREST);
+    let mut info = MONITORINFO {
+        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+    };
+    if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+        return false;
+    }
+    let screen = info.rcMonitor;
+    rect.left <= screen.left
+        && rect.top <= screen.top
+        && rect.right >= screen.right
+        && rect.bottom >= screen.bottom
+}
+
+impl ForegroundProvider for Win32Foreground {
+    fn name(&self) -> &'static str {
+        "win32"
+    }
+
+    fn foreground(&mut self) -> Option<Foreground> {
+        unsafe {
+            let window = GetForegroundWindow();
+            if window == HWND::default() {
+                return None;
+            }
+            let process = process_name(window);
+            // 桌面本身也铺满屏幕，不算全屏应用
+            let desktop = window == GetShellWindow()
+                || window == GetDesktopWindow()
+                || process.eq_ignore_ascii_case("explorer");
+            Some(Foreground {
+                fullscreen: !desktop && covers_monitor(window),
+                process,
+            })
+        }
+    }
+}
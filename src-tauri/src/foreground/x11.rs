@@ -0,0 +1,145 @@
+// X11：按 EWMH 读取 _NET_ACTIVE_WINDOW，再看它的 _NET_WM_STATE 是否带全屏、_NET_WM_PID 属于哪个进程
+use super::{Foreground, ForegroundProvider};
+use std::ffi::CString;
+use std::fs;
+use std::os::raw::{c_int, c_long, c_uchar, c_ulong};
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::OnceLock;
+use x11_dl::xlib::{self, Display, XErrorEvent, Xlib};
+
+type ErrorHandler = unsafe extern "C" fn(*mut Display, *mut XErrorEvent) -> c_int;
+
+static OWN_DISPLAY: AtomicPtr<Display> = AtomicPtr::new(ptr::null_mut());
+static PREVIOUS_HANDLER: OnceLock<Option<ErrorHandler>> = OnceLock::new();
+
+/// 前台窗口随时可能被关掉，再查询会收到 BadWindow，而 Xlib 默认的处理是直接退出进程；
+/// 这里只吞掉本连接上的错误，其他连接仍交给原来的处理函数
+unsafe extern "C" fn ignore_own_errors(display: *mut Display, event: *mut XErrorEvent) -> c_int {
+    if display == OWN_DISPLAY.load(Ordering::SeqCst) {
+        return 0;
+    }
+    match PREVIOUS_HANDLER.get().copied().flatten() {
+        Some(previous) => previous(display, event),
+        None => 0,
+    }
+}
+
+pub struct X11Foreground {
+    xlib: Xlib,
+    display: *mut Display,
+    root: c_ulong,
+    net_active_window: c_ulong,
+    net_wm_state: c_ulong,
+    net_wm_state_fullscreen: c_ulong,
+    net_wm_pid: c_ulong,
+}
+
+// Display 指针只在休息调度线程内使用
+unsafe impl Send for X11Foreground {}
+
+impl X11Foreground {
+    pub fn open() -> Result<Self, String> {
+        let xlib = Xlib::open().map_err(|err| err.to_string())?;
+        unsafe {
+            let display = (xlib.XOpenDisplay)(ptr::null());
+            if display.is_null() {
+                return Err("无法连接 X11 显示服务".into());
+            }
+            let atom = |name: &str| {
+                let name = CString::new(name).unwrap_or_default();
+                (xlib.XInternAtom)(display, name.as_ptr(), xlib::False)
+            };
+            let net_active_window = atom("_NET_ACTIVE_WINDOW");
+            let net_wm_state = atom("_NET_WM_STATE");
+            let net_wm_state_fullscreen = atom("_NET_WM_STATE_FULLSCREEN");
+            let net_wm_pid = atom("_NET_WM_PID");
+            OWN_DISPLAY.store(display, Ordering::SeqCst);
+            PREVIOUS_HANDLER.get_or_init(|| (xlib.XSetErrorHandler)(Some(ignore_own_errors)));
+            Ok(Self {
+                root: (xlib.XDefaultRootWindow)(display),
+                xlib,
+                display,
+                net_active_window,
+                net_wm_state,
+                net_wm_state_fullscreen,
+                net_wm_pid,
+            })
+        }
+    }
+
+    /// 读取 32 位格式的属性，Xlib 以 long 数组返回
+    unsafe fn property(&self, window: c_ulong, property: c_ulong, kind: c_ulong) -> Vec<c_ulong> {
+        let mut actual_type: c_ulong = 0;
+        let mut format: c_int = 0;
+        let mut count: c_ulong = 0;
+        let mut remaining: c_ulong = 0;
+        let mut data: *mut c_uchar = ptr::null_mut();
+        let status = (self.xlib.XGetWindowProperty)(
+            self.display,
+            window,
+            property,
+            0,
+            64,
+            xlib::False,
+            kind,
+            &mut actual_type,
+            &mut format,
+            &mut count,
+            &mut remaining,
+            &mut data,
+        );
+        if status != xlib::Success as c_int || data.is_null() {
+            return Vec::new();
+        }
+        let values = if format == 32 {
+            std::slice::from_raw_parts(data as *const c_long, count as usize)
+                .iter()
+                .map(|value| *value as c_ulong)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        (self.xlib.XFree)(data.cast());
+        values
+    }
+}
+
+impl Drop for X11Foreground {
+    fn drop(&mut self) {
+        OWN_DISPLAY.store(ptr::null_mut(), Ordering::SeqCst);
+        unsafe {
+            (self.xlib.XCloseDisplay)(self.display);
+        }
+    }
+}
+
+impl ForegroundProvider for X11Foreground {
+    fn name(&self) -> &'static str {
+        "x11"
+    }
+
+    fn foreground(&mut self) -> Option<Foreground> {
+        unsafe {
+            let window = *self
+                .property(self.root, self.net_active_window, xlib::XA_WINDOW)
+                .first()?;
+            if window == 0 {
+                return None;
+            }
+            let fullscreen = self
+                .property(window, self.net_wm_state, xlib::XA_ATOM)
+                .contains(&self.net_wm_state_fullscreen);
+            let process = self
+                .property(window, self.net_wm_pid, xlib::XA_CARDINAL)
+                .first()
+                .and_then(|pid| fs::read_to_string(format!("/proc/{}/comm", pid)).ok())
+                .map(|name| name.trim().to_string())
+                .unwrap_or_default();
+            Some(Foreground {
+                process,
+                fullscreen,
+            })
+        }
+    }
+}
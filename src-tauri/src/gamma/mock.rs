@@ -0,0 +1,91 @@
+// 测试用后端：记录每次写入的曲线，不接触真实显示设备
+use super::{GammaBackend, GammaOutput, GammaRamp, DEFAULT_RAMP_SIZE};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct MockInner {
+    outputs: Vec<GammaOutput>,
+    current: HashMap<String, GammaRamp>,
+    writes: Vec<(String, GammaRamp)>,
+}
+
+/// 克隆出的句柄共享同一份记录，交给 GammaState 之后仍可在测试里检查
+#[derive(Clone, Default)]
+pub struct MockBackend {
+    inner: Arc<Mutex<MockInner>>,
+}
+
+impl MockBackend {
+    pub fn new(outputs: &[&str]) -> Self {
+        let backend = Self::default();
+        {
+            let mut inner = backend.inner.lock().unwrap();
+            for id in outputs {
+                inner.outputs.push(GammaOutput {
+                    id: id.to_string(),
+                    ramp_size: DEFAULT_RAMP_SIZE,
+                    bounds: None,
+                });
+                inner
+                    .current
+                    .insert(id.to_string(), GammaRamp::linear(DEFAULT_RAMP_SIZE));
+            }
+        }
+        backend
+    }
+
+    pub fn writes(&self) -> Vec<(String, GammaRamp)> {
+        self.inner.lock().unwrap().writes.clone()
+    }
+
+    /// 模拟其他程序直接改写了曲线，不计入写入记录
+    pub fn clobber(&self, output: &str, ramp: GammaRamp) {
+        self.inner
+            .lock()
+            .unwrap()
+            .current
+            .insert(output.to_string(), ramp);
+    }
+
+    pub fn last_ramp(&self, output: &str) -> Option<GammaRamp> {
+        self.inner
+            .lock()
+            .unwrap()
+            .writes
+            .iter()
+            .rev()
+            .find(|(id, _)| id == output)
+            .map(|(_, ramp)| ramp.clone())
+    }
+}
+
+impl GammaBackend for MockBackend {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn outputs(&mut self) -> Result<Vec<GammaOutput>, String> {
+        Ok(self.inner.lock().unwrap().outputs.clone())
+    }
+
+    fn get_ramp(&mut self, output: &str) -> Result<GammaRamp, String> {
+        self.inner
+            .lock()
+            .unwrap()
+            .current
+            .get(output)
+            .cloned()
+            .ok_or_else(|| format!("未找到显示输出: {}", output))
+    }
+
+    fn set_ramp(&mut self, output: &str, ramp: &GammaRamp) -> Result<(), String> {
+        let mut inner = self.inner.lock().unwrap();
+        if !inner.current.contains_key(output) {
+            return Err(format!("未找到显示输出: {}", output));
+        }
+        inner.current.insert(output.to_string(), ramp.clone());
+        inner.writes.push((output.to_string(), ramp.clone()));
+        Ok(())
+    }
+}
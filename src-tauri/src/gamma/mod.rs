@@ -0,0 +1,761 @@
+// 显示 gamma 曲线的平台后端：Windows 走 GDI，Linux 走 Wayland wlr-gamma-control 或 X11 RandR
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+#[cfg(test)]
+pub mod mock;
+pub mod recovery;
+#[cfg(all(target_os = "linux", feature = "wayland"))]
+mod wayland;
+#[cfg(windows)]
+mod win32;
+#[cfg(all(target_os = "linux", feature = "x11"))]
+mod x11;
+
+/// Win32 GDI 固定使用 256 级 gamma 表
+pub const DEFAULT_RAMP_SIZE: usize = 256;
+/// 驱动回读时可能做量化，低于该偏差不算被外部改写
+const DRIFT_TOLERANCE: u16 = 512;
+
+/// 亮度/对比度/逐通道 gamma 指数，构建 gamma 表时叠加在色温倍率之上
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToneCurve {
+    pub brightness: f64,
+    pub contrast: f64,
+    pub gamma_red: f64,
+    pub gamma_green: f64,
+    pub gamma_blue: f64,
+}
+
+// 下限保证最亮处仍有可见亮度，避免把屏幕调成全黑
+pub const BRIGHTNESS_RANGE: RangeInclusive<f64> = 0.1..=1.0;
+pub const CONTRAST_RANGE: RangeInclusive<f64> = 0.5..=1.5;
+pub const GAMMA_EXPONENT_RANGE: RangeInclusive<f64> = 0.5..=2.5;
+
+impl Default for ToneCurve {
+    fn default() -> Self {
+        Self {
+            brightness: 1.0,
+            contrast: 1.0,
+            gamma_red: 1.0,
+            gamma_green: 1.0,
+            gamma_blue: 1.0,
+        }
+    }
+}
+
+impl ToneCurve {
+    pub fn validate(&self) -> Result<(), String> {
+        if !BRIGHTNESS_RANGE.contains(&self.brightness) {
+            return Err("亮度需在 10% 到 100% 之间".into());
+        }
+        if !CONTRAST_RANGE.contains(&self.contrast) {
+            return Err("对比度需在 50% 到 150% 之间".into());
+        }
+        for value in [self.gamma_red, self.gamma_green, self.gamma_blue] {
+            if !GAMMA_EXPONENT_RANGE.contains(&value) {
+                return Err("gamma 指数需在 0.5 到 2.5 之间".into());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 依次做 gamma 指数、以中灰为轴的对比度、亮度缩放
+    fn map(&self, value: f64, exponent: f64) -> f64 {
+        let curved = value.clamp(0.0, 1.0).powf(1.0 / exponent);
+        let contrasted = (curved - 0.5) * self.contrast + 0.5;
+        (contrasted * self.brightness).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GammaRamp {
+    pub red: Vec<u16>,
+    pub green: Vec<u16>,
+    pub blue: Vec<u16>,
+}
+
+impl GammaRamp {
+    pub fn linear(size: usize) -> Self {
+        Self::scaled(size, 1.0, 1.0, 1.0)
+    }
+
+    /// 在线性曲线基础上按通道缩放
+    pub fn scaled(size: usize, mult_r: f64, mult_g: f64, mult_b: f64) -> Self {
+        let channel = |mult: f64| -> Vec<u16> {
+            (0..size)
+                .map(|i| {
+                    let base = if size > 1 {
+                        i as f64 / (size - 1) as f64
+                    } else {
+                        1.0
+                    };
+                    (base * 65535.0 * mult).clamp(0.0, 65535.0).round() as u16
+                })
+                .collect()
+        };
+        Self {
+            red: channel(mult_r),
+            green: channel(mult_g),
+            blue: channel(mult_b),
+        }
+    }
+
+    /// 在已有曲线（如显示器校色曲线）基础上按通道缩放
+    pub fn multiplied(&self, mult_r: f64, mult_g: f64, mult_b: f64) -> Self {
+        let channel = |values: &[u16], mult: f64| -> Vec<u16> {
+            values
+                .iter()
+                .map(|value| (*value as f64 * mult).clamp(0.0, 65535.0).round() as u16)
+                .collect()
+        };
+        Self {
+            red: channel(&self.red, mult_r),
+            green: channel(&self.green, mult_g),
+            blue: channel(&self.blue, mult_b),
+        }
+    }
+
+    /// 先套用亮度/对比度/gamma 指数，再按通道缩放
+    pub fn adjusted(&self, multipliers: (f64, f64, f64), tone: &ToneCurve) -> Self {
+        let (mult_r, mult_g, mult_b) = multipliers;
+        if tone.is_identity() {
+            return self.multiplied(mult_r, mult_g, mult_b);
+        }
+        let channel = |values: &[u16], mult: f64, exponent: f64| -> Vec<u16> {
+            values
+                .iter()
+                .map(|value| {
+                    let mapped = tone.map(*value as f64 / 65535.0, exponent);
+                    (mapped * mult * 65535.0).clamp(0.0, 65535.0).round() as u16
+                })
+                .collect()
+        };
+        Self {
+            red: channel(&self.red, mult_r, tone.gamma_red),
+            green: channel(&self.green, mult_g, tone.gamma_green),
+            blue: channel(&self.blue, mult_b, tone.gamma_blue),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.red.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.red.is_empty()
+    }
+
+    /// 三个通道里逐项差值的最大值，长度不同视为完全不一致
+    pub fn max_delta(&self, other: &GammaRamp) -> u16 {
+        if self.len() != other.len() {
+            return u16::MAX;
+        }
+        [
+            (&self.red, &other.red),
+            (&self.green, &other.green),
+            (&self.blue, &other.blue),
+        ]
+        .iter()
+        .flat_map(|(a, b)| a.iter().zip(b.iter()).map(|(x, y)| x.abs_diff(*y)))
+        .max()
+        .unwrap_or(0)
+    }
+}
+
+/// 输出在桌面上的位置和大小，物理像素
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GammaOutput {
+    /// 平台输出标识：Windows 为 `\\.\DISPLAY1`，X11/Wayland 为 `HDMI-1` 之类的接口名
+    pub id: String,
+    pub ramp_size: usize,
+    /// 用来和界面里的显示器对应；后端给不出时为 None
+    pub bounds: Option<OutputBounds>,
+}
+
+pub trait GammaBackend: Send {
+    fn name(&self) -> &'static str;
+
+    fn outputs(&mut self) -> Result<Vec<GammaOutput>, String>;
+
+    fn get_ramp(&mut self, output: &str) -> Result<GammaRamp, String>;
+
+    fn set_ramp(&mut self, output: &str, ramp: &GammaRamp) -> Result<(), String>;
+
+    fn reset(&mut self, output: &str) -> Result<(), String> {
+        let size = self
+            .outputs()?
+            .into_iter()
+            .find(|item| item.id == output)
+            .map(|item| item.ramp_size)
+            .ok_or_else(|| format!("未找到显示输出: {}", output))?;
+        self.set_ramp(output, &GammaRamp::linear(size))
+    }
+}
+
+/// 当前平台/构建没有可用后端时的占位实现
+struct UnsupportedBackend;
+
+impl GammaBackend for UnsupportedBackend {
+    fn name(&self) -> &'static str {
+        "unsupported"
+    }
+
+    fn outputs(&mut self) -> Result<Vec<GammaOutput>, String> {
+        Err("当前平台不支持调节色温".into())
+    }
+
+    fn get_ramp(&mut self, _output: &str) -> Result<GammaRamp, String> {
+        Err("当前平台不支持调节色温".into())
+    }
+
+    fn set_ramp(&mut self, _output: &str, _ramp: &GammaRamp) -> Result<(), String> {
+        Err("当前平台不支持调节色温".into())
+    }
+}
+
+#[cfg(windows)]
+pub fn default_backend() -> Box<dyn GammaBackend> {
+    Box::new(win32::Win32Backend)
+}
+
+#[cfg(not(windows))]
+pub fn default_backend() -> Box<dyn GammaBackend> {
+    // Wayland 会话下 XWayland 的 RandR gamma 不生效，优先尝试 wlr 协议
+    #[cfg(all(target_os = "linux", feature = "wayland"))]
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        if let Ok(backend) = wayland::WaylandBackend::connect() {
+            return Box::new(backend);
+        }
+    }
+    #[cfg(all(target_os = "linux", feature = "x11"))]
+    if let Ok(backend) = x11::X11Backend::open() {
+        return Box::new(backend);
+    }
+    Box::new(UnsupportedBackend)
+}
+
+#[derive(Debug, Clone)]
+pub struct GammaDrift {
+    pub output: String,
+    pub max_delta: u16,
+}
+
+#[derive(Default)]
+struct MarkerState {
+    path: Option<PathBuf>,
+    written: bool,
+}
+
+pub struct GammaState {
+    backend: Mutex<Box<dyn GammaBackend>>,
+    /// 最近一次写入的非中性曲线，供看门狗比对
+    applied: Mutex<HashMap<String, GammaRamp>>,
+    /// 每个输出在本进程第一次修改前的曲线
+    originals: Mutex<HashMap<String, GammaRamp>>,
+    marker: Mutex<MarkerState>,
+    tone: Mutex<ToneCurve>,
+}
+
+impl Default for GammaState {
+    fn default() -> Self {
+        Self::with_backend(default_backend())
+    }
+}
+
+impl GammaState {
+    pub fn with_backend(backend: Box<dyn GammaBackend>) -> Self {
+        Self {
+            backend: Mutex::new(backend),
+            applied: Mutex::new(HashMap::new()),
+            originals: Mutex::new(HashMap::new()),
+            marker: Mutex::new(MarkerState::default()),
+            tone: Mutex::new(ToneCurve::default()),
+        }
+    }
+
+    pub fn backend_name(&self) -> String {
+        self.backend
+            .lock()
+            .map(|backend| backend.name().to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn tone(&self) -> ToneCurve {
+        self.tone.lock().map(|value| *value).unwrap_or_default()
+    }
+
+    /// 超出范围的参数直接拒绝，不做截断
+    pub fn set_tone(&self, tone: ToneCurve) -> Result<(), String> {
+        tone.validate()?;
+        let mut current = self.tone.lock().map_err(|_| "gamma 后端被占用")?;
+        *current = tone;
+        Ok(())
+    }
+
+    pub fn set_marker_path(&self, path: PathBuf) {
+        if let Ok(mut marker) = self.marker.lock() {
+            marker.path = Some(path);
+        }
+    }
+
+    /// 上次运行异常退出留下标记时，还原成当时记录的原始曲线
+    pub fn recover_from_marker(&self) -> Result<Option<usize>, String> {
+        let Some(path) = self
+            .marker
+            .lock()
+            .ok()
+            .and_then(|marker| marker.path.clone())
+        else {
+            return Ok(None);
+        };
+        let Some(saved) = recovery::read_marker(&path) else {
+            return Ok(None);
+        };
+        if saved.owner_running() {
+            return Err(format!(
+                "另一个护眼吧进程（pid {}）仍在运行，未还原 gamma",
+                saved.pid
+            ));
+        }
+        let mut backend = self.backend.lock().map_err(|_| "gamma 后端被占用")?;
+        let count = recovery::restore(backend.as_mut(), &saved)?;
+        recovery::remove_marker(&path);
+        Ok(Some(count))
+    }
+
+    /// 有非中性曲线生效时保证标记存在，全部还原后删除
+    fn sync_marker(
+        &self,
+        backend_name: &str,
+        applied: &HashMap<String, GammaRamp>,
+        originals_changed: bool,
+    ) {
+        let Ok(mut marker) = self.marker.lock() else {
+            return;
+        };
+        let Some(path) = marker.path.clone() else {
+            return;
+        };
+        if applied.is_empty() {
+            if marker.written {
+                recovery::remove_marker(&path);
+                marker.written = false;
+            }
+            return;
+        }
+        if marker.written && !originals_changed {
+            return;
+        }
+        let originals = self
+            .originals
+            .lock()
+            .map(|value| value.clone())
+            .unwrap_or_default();
+        let saved = recovery::DirtyMarker::new(backend_name, originals);
+        marker.written = recovery::write_marker(&path, &saved).is_ok();
+    }
+
+    pub fn outputs(&self) -> Result<Vec<GammaOutput>, String> {
+        let mut backend = self.backend.lock().map_err(|_| "gamma 后端被占用")?;
+        backend.outputs()
+    }
+
+    /// 对所有输出写入按通道缩放后的曲线
+    pub fn apply(&self, mult_r: f64, mult_g: f64, mult_b: f64) -> Result<(), String> {
+        self.apply_with(|_| Some((mult_r, mult_g, mult_b)))
+    }
+
+    /// 按输出分别计算倍率，返回 None 的输出只保留亮度等调节，没有调节时还原为原始曲线
+    pub fn apply_with(
+        &self,
+        multipliers: impl Fn(&str) -> Option<(f64, f64, f64)>,
+    ) -> Result<(), String> {
+        let mut backend = self.backend.lock().map_err(|_| "gamma 后端被占用")?;
+        let mut applied = self.applied.lock().map_err(|_| "gamma 后端被占用")?;
+        let outputs = backend.outputs()?;
+        if outputs.is_empty() {
+            return Err("无法获取显示设备句柄".into());
+        }
+        applied.retain(|id, _| outputs.iter().any(|output| &output.id == id));
+        let tone = self.tone();
+        let mut originals_changed = false;
+        for output in outputs {
+            // 任何写入之前先记下原曲线，之后的滤镜和还原都以它为准
+            if self.capture_original(backend.as_mut(), &output.id) {
+                originals_changed = true;
+            }
+            let target = match multipliers(&output.id) {
+                Some(mult) => Some(mult),
+                None if !tone.is_identity() => Some((1.0, 1.0, 1.0)),
+                None => None,
+            };
+            match target {
+                Some(mult) => {
+                    let base = self
+                        .original_for(&output)
+                        .unwrap_or_else(|| GammaRamp::linear(output.ramp_size));
+                    let ramp = base.adjusted(mult, &tone);
+                    backend.set_ramp(&output.id, &ramp)?;
+                    applied.insert(output.id, ramp);
+                }
+                None => {
+                    self.restore_original(backend.as_mut(), &output)?;
+                    applied.remove(&output.id);
+                }
+            }
+        }
+        self.sync_marker(backend.name(), &applied, originals_changed);
+        Ok(())
+    }
+
+    /// 首次修改某个输出前记录它原来的曲线，返回是否新记录
+    fn capture_original(&self, backend: &mut dyn GammaBackend, output: &str) -> bool {
+        let Ok(mut originals) = self.originals.lock() else {
+            return false;
+        };
+        if originals.contains_key(output) {
+            return false;
+        }
+        match backend.get_ramp(output) {
+            Ok(ramp) => {
+                originals.insert(output.to_string(), ramp);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// 记录过且长度匹配的原曲线
+    fn original_for(&self, output: &GammaOutput) -> Option<GammaRamp> {
+        self.originals
+            .lock()
+            .ok()?
+            .get(&output.id)
+            .filter(|ramp| ramp.len() == output.ramp_size)
+            .cloned()
+    }
+
+    /// 写回原曲线；无法回读的后端交给它自己的 reset
+    fn restore_original(
+        &self,
+        backend: &mut dyn GammaBackend,
+        output: &GammaOutput,
+    ) -> Result<(), String> {
+        match self.original_for(output) {
+            Some(original) => backend.set_ramp(&output.id, &original),
+            None => backend.reset(&output.id),
+        }
+    }
+
+    /// 本进程记录过原曲线的输出写回原曲线，其余写线性曲线；
+    /// 这里不回读，否则残留的色调会被当成原曲线写回去
+    pub fn reset(&self) -> Result<(), String> {
+        let mut backend = self.backend.lock().map_err(|_| "gamma 后端被占用")?;
+        let mut applied = self.applied.lock().map_err(|_| "gamma 后端被占用")?;
+        applied.clear();
+        for output in backend.outputs()? {
+            self.restore_original(backend.as_mut(), &output)?;
+        }
+        self.sync_marker(backend.name(), &applied, false);
+        Ok(())
+    }
+
+    /// 回读各输出当前曲线，与最近写入的不一致时重新写入并返回偏差
+    pub fn restore_drifted(&self) -> Result<Vec<GammaDrift>, String> {
+        let mut backend = self.backend.lock().map_err(|_| "gamma 后端被占用")?;
+        let applied = self.applied.lock().map_err(|_| "gamma 后端被占用")?;
+        let mut drifts = Vec::new();
+        for (output, expected) in applied.iter() {
+            // Wayland 等无法回读的后端直接跳过
+            let Ok(actual) = backend.get_ramp(output) else {
+                continue;
+            };
+            let max_delta = actual.max_delta(expected);
+            if max_delta > DRIFT_TOLERANCE {
+                backend.set_ramp(output, expected)?;
+                drifts.push(GammaDrift {
+                    output: output.clone(),
+                    max_delta,
+                });
+            }
+        }
+        Ok(drifts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_ramp_spans_full_range() {
+        let ramp = GammaRamp::linear(DEFAULT_RAMP_SIZE);
+        assert_eq!(ramp.len(), 256);
+        assert_eq!(ramp.red[0], 0);
+        assert_eq!(ramp.red[255], 65535);
+        assert_eq!(ramp.red, ramp.green);
+        assert_eq!(ramp.green, ramp.blue);
+    }
+
+    #[test]
+    fn scaled_ramp_clamps_to_u16() {
+        let ramp = GammaRamp::scaled(DEFAULT_RAMP_SIZE, 1.5, 0.5, 0.0);
+        assert_eq!(ramp.red[255], 65535);
+        assert_eq!(ramp.green[255], 32768);
+        assert!(ramp.blue.iter().all(|value| *value == 0));
+    }
+
+    #[test]
+    fn state_writes_every_output() {
+        let mock = mock::MockBackend::new(&["DISPLAY1", "DISPLAY2"]);
+        let state = GammaState::with_backend(Box::new(mock.clone()));
+        state.apply(1.0, 0.9, 0.8).unwrap();
+        let writes = mock.writes();
+        assert_eq!(writes.len(), 2);
+        assert_eq!(writes[0].0, "DISPLAY1");
+        assert_eq!(writes[1].0, "DISPLAY2");
+        assert_eq!(writes[0].1, writes[1].1);
+    }
+
+    #[test]
+    fn watchdog_restores_clobbered_ramp() {
+        let mock = mock::MockBackend::new(&["DISPLAY1", "DISPLAY2"]);
+        let state = GammaState::with_backend(Box::new(mock.clone()));
+        state.apply(1.0, 0.8, 0.6).unwrap();
+        let expected = mock.last_ramp("DISPLAY1").unwrap();
+
+        mock.clobber("DISPLAY1", GammaRamp::linear(DEFAULT_RAMP_SIZE));
+        let drifts = state.restore_drifted().unwrap();
+        assert_eq!(drifts.len(), 1);
+        assert_eq!(drifts[0].output, "DISPLAY1");
+        assert!(drifts[0].max_delta > DRIFT_TOLERANCE);
+        assert_eq!(mock.last_ramp("DISPLAY1"), Some(expected));
+        assert!(state.restore_drifted().unwrap().is_empty());
+    }
+
+    #[test]
+    fn watchdog_ignores_small_quantization() {
+        let mock = mock::MockBackend::new(&["DISPLAY1"]);
+        let state = GammaState::with_backend(Box::new(mock.clone()));
+        state.apply(1.0, 0.8, 0.6).unwrap();
+        let mut quantized = mock.last_ramp("DISPLAY1").unwrap();
+        for value in quantized.red.iter_mut() {
+            *value &= !0xff;
+        }
+        mock.clobber("DISPLAY1", quantized);
+        assert!(state.restore_drifted().unwrap().is_empty());
+    }
+
+    #[test]
+    fn watchdog_ignores_outputs_after_reset() {
+        let mock = mock::MockBackend::new(&["DISPLAY1"]);
+        let state = GammaState::with_backend(Box::new(mock.clone()));
+        state.apply(1.0, 0.8, 0.6).unwrap();
+        state.reset().unwrap();
+        mock.clobber(
+            "DISPLAY1",
+            GammaRamp::scaled(DEFAULT_RAMP_SIZE, 0.5, 0.5, 0.5),
+        );
+        assert!(state.restore_drifted().unwrap().is_empty());
+    }
+
+    fn marker_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "huyanba-gamma-test-{}-{}",
+            std::process::id(),
+            name
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(recovery::MARKER_FILE);
+        recovery::remove_marker(&path);
+        path
+    }
+
+    #[test]
+    fn marker_tracks_non_neutral_ramp() {
+        let path = marker_path("tracks");
+        let mock = mock::MockBackend::new(&["DISPLAY1"]);
+        let state = GammaState::with_backend(Box::new(mock.clone()));
+        state.set_marker_path(path.clone());
+
+        state.apply(1.0, 0.8, 0.6).unwrap();
+        let saved = recovery::read_marker(&path).expect("应写入崩溃标记");
+        assert_eq!(
+            saved.originals.get("DISPLAY1"),
+            Some(&GammaRamp::linear(DEFAULT_RAMP_SIZE))
+        );
+
+        state.reset().unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn recovery_restores_original_ramp_after_crash() {
+        let path = marker_path("recover");
+        let calibrated = GammaRamp::scaled(DEFAULT_RAMP_SIZE, 0.95, 0.97, 0.9);
+        let mock = mock::MockBackend::new(&["DISPLAY1"]);
+        mock.clobber("DISPLAY1", calibrated.clone());
+        {
+            let crashed = GammaState::with_backend(Box::new(mock.clone()));
+            crashed.set_marker_path(path.clone());
+            crashed.apply(1.0, 0.7, 0.5).unwrap();
+            // 模拟进程被杀：不调用 reset
+        }
+        let restarted = GammaState::with_backend(Box::new(mock.clone()));
+        restarted.set_marker_path(path.clone());
+        assert_eq!(restarted.recover_from_marker().unwrap(), Some(1));
+        assert_eq!(mock.last_ramp("DISPLAY1"), Some(calibrated));
+        assert!(!path.exists());
+        assert_eq!(restarted.recover_from_marker().unwrap(), None);
+    }
+
+    #[test]
+    fn filter_scales_calibrated_baseline() {
+        let calibrated = GammaRamp::scaled(DEFAULT_RAMP_SIZE, 0.9, 0.95, 0.85);
+        let mock = mock::MockBackend::new(&["DISPLAY1"]);
+        mock.clobber("DISPLAY1", calibrated.clone());
+        let state = GammaState::with_backend(Box::new(mock.clone()));
+        state.apply(1.0, 0.5, 0.5).unwrap();
+        assert_eq!(
+            mock.last_ramp("DISPLAY1"),
+            Some(calibrated.multiplied(1.0, 0.5, 0.5))
+        );
+    }
+
+    #[test]
+    fn reset_restores_calibrated_ramp() {
+        let calibrated = GammaRamp::scaled(DEFAULT_RAMP_SIZE, 0.9, 0.95, 0.85);
+        let mock = mock::MockBackend::new(&["DISPLAY1"]);
+        mock.clobber("DISPLAY1", calibrated.clone());
+        let state = GammaState::with_backend(Box::new(mock.clone()));
+        state.apply(1.0, 0.7, 0.4).unwrap();
+        state.apply(1.0, 0.6, 0.3).unwrap();
+        state.reset().unwrap();
+        assert_eq!(mock.last_ramp("DISPLAY1"), Some(calibrated.clone()));
+
+        // 关闭单个输出的滤镜也回到校色曲线
+        state.apply(1.0, 0.6, 0.3).unwrap();
+        state.apply_with(|_| None).unwrap();
+        assert_eq!(mock.last_ramp("DISPLAY1"), Some(calibrated));
+    }
+
+    #[test]
+    fn tone_curve_rejects_black_screen() {
+        let mut tone = ToneCurve {
+            brightness: 0.0,
+            ..ToneCurve::default()
+        };
+        assert!(tone.validate().is_err());
+        tone.brightness = 0.1;
+        tone.contrast = 0.2;
+        assert!(tone.validate().is_err());
+        tone.contrast = 1.0;
+        tone.gamma_blue = 0.0;
+        assert!(tone.validate().is_err());
+        tone.gamma_blue = f64::NAN;
+        assert!(tone.validate().is_err());
+
+        let state = GammaState::with_backend(Box::new(mock::MockBackend::new(&["DISPLAY1"])));
+        assert!(state
+            .set_tone(ToneCurve {
+                brightness: 0.05,
+                ..ToneCurve::default()
+            })
+            .is_err());
+        assert!(state.tone().is_identity());
+    }
+
+    #[test]
+    fn tone_curve_dims_and_stays_monotonic() {
+        let linear = GammaRamp::linear(DEFAULT_RAMP_SIZE);
+        let tone = ToneCurve {
+            brightness: 0.1,
+            contrast: 0.5,
+            gamma_red: 0.5,
+            gamma_green: 2.5,
+            gamma_blue: 1.0,
+        };
+        let ramp = linear.adjusted((1.0, 1.0, 1.0), &tone);
+        let top = DEFAULT_RAMP_SIZE - 1;
+        for channel in [&ramp.red, &ramp.green, &ramp.blue] {
+            assert!(channel.windows(2).all(|pair| pair[0] <= pair[1]));
+            assert!(channel[top] > 0);
+            assert!(channel[top] < linear.red[top]);
+        }
+        // gamma 指数大于 1 时中间调更亮
+        assert!(ramp.green[128] > ramp.red[128]);
+        assert_eq!(
+            linear.adjusted((1.0, 0.5, 0.5), &ToneCurve::default()),
+            linear.multiplied(1.0, 0.5, 0.5)
+        );
+    }
+
+    #[test]
+    fn tone_curve_applies_with_filter_disabled() {
+        let mock = mock::MockBackend::new(&["DISPLAY1"]);
+        let state = GammaState::with_backend(Box::new(mock.clone()));
+        state
+            .set_tone(ToneCurve {
+                brightness: 0.5,
+                ..ToneCurve::default()
+            })
+            .unwrap();
+        state.apply_with(|_| None).unwrap();
+        let dimmed = mock.last_ramp("DISPLAY1").unwrap();
+        assert_eq!(dimmed.red[255], 32768);
+
+        // 退出时的 reset 无视亮度调节，回到原曲线
+        state.reset().unwrap();
+        assert_eq!(
+            mock.last_ramp("DISPLAY1"),
+            Some(GammaRamp::linear(DEFAULT_RAMP_SIZE))
+        );
+    }
+
+    #[test]
+    fn reset_without_apply_clears_leftover_tint() {
+        // 上次运行留下的色调，本进程没写过任何曲线
+        let mock = mock::MockBackend::new(&["DISPLAY1"]);
+        mock.clobber(
+            "DISPLAY1",
+            GammaRamp::scaled(DEFAULT_RAMP_SIZE, 1.0, 0.6, 0.3),
+        );
+        let state = GammaState::with_backend(Box::new(mock.clone()));
+        state.reset().unwrap();
+        assert_eq!(
+            mock.last_ramp("DISPLAY1"),
+            Some(GammaRamp::linear(DEFAULT_RAMP_SIZE))
+        );
+    }
+
+    #[test]
+    fn reset_writes_linear_ramp() {
+        let mock = mock::MockBackend::new(&["DISPLAY1"]);
+        let state = GammaState::with_backend(Box::new(mock.clone()));
+        state.apply(1.0, 0.5, 0.5).unwrap();
+        state.reset().unwrap();
+        assert_eq!(
+            mock.last_ramp("DISPLAY1"),
+            Some(GammaRamp::linear(DEFAULT_RAMP_SIZE))
+        );
+    }
+}
@@ -0,0 +1,146 @@
+// 崩溃保护：滤镜生效期间在磁盘上留一个标记，记录首次修改前的原始曲线。
+// 进程被强杀时退出钩子不会执行，下次启动（或 --restore-gamma）据此还原屏幕。
+use super::{GammaBackend, GammaRamp};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const MARKER_FILE: &str = "gamma_dirty.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirtyMarker {
+    pub pid: u32,
+    pub backend: String,
+    pub created_at: i64,
+    /// 无法回读曲线的后端（如 Wayland）这里为空，恢复时走后端自己的 reset
+    pub originals: HashMap<String, GammaRamp>,
+}
+
+impl DirtyMarker {
+    pub fn new(backend: &str, originals: HashMap<String, GammaRamp>) -> Self {
+        Self {
+            pid: std::process::id(),
+            backend: backend.to_string(),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|value| value.as_secs() as i64)
+                .unwrap_or(0),
+            originals,
+        }
+    }
+
+    /// 写标记的进程仍在运行且是本程序，说明另一个实例正开着滤镜，不能替它还原
+    pub fn owner_running(&self) -> bool {
+        self.pid != std::process::id() && is_this_binary(self.pid)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_this_binary(pid: u32) -> bool {
+    let Ok(exe) = fs::read_link(format!("/proc/{}/exe", pid)) else {
+        return false;
+    };
+    std::env::current_exe().is_ok_and(|current| current == exe)
+}
+
+#[cfg(windows)]
+fn is_this_binary(pid: u32) -> bool {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    // GetExitCodeProcess 对仍在运行的进程返回 STILL_ACTIVE
+    const STILL_ACTIVE: u32 = 259;
+
+    let Ok(current) = std::env::current_exe() else {
+        return false;
+    };
+    unsafe {
+        let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) else {
+            return false;
+        };
+        let mut code = 0u32;
+        let alive = GetExitCodeProcess(process, &mut code).is_ok() && code == STILL_ACTIVE;
+        let mut buffer = [0u16; 1024];
+        let mut size = buffer.len() as u32;
+        let queried = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut size,
+        );
+        let _ = CloseHandle(process);
+        if !alive || queried.is_err() {
+            return false;
+        }
+        let exe = String::from_utf16_lossy(&buffer[..size as usize]);
+        exe.eq_ignore_ascii_case(&current.to_string_lossy())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn is_this_binary(_pid: u32) -> bool {
+    false
+}
+
+pub fn read_marker(path: &Path) -> Option<DirtyMarker> {
+    let data = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+/// 先写临时文件再改名，避免写到一半被杀留下残缺的标记
+pub fn write_marker(path: &Path, marker: &DirtyMarker) -> Result<(), String> {
+    let data = serde_json::to_string(marker).map_err(|err| err.to_string())?;
+    crate::settings::write_atomic(path, data.as_bytes())
+}
+
+pub fn remove_marker(path: &Path) {
+    let _ = fs::remove_file(path);
+}
+
+/// 按标记还原所有当前输出，返回处理的输出数量
+pub fn restore(backend: &mut dyn GammaBackend, marker: &DirtyMarker) -> Result<usize, String> {
+    let outputs = backend.outputs()?;
+    for output in outputs.iter() {
+        match marker.originals.get(&output.id) {
+            Some(original) if original.len() == output.ramp_size => {
+                backend.set_ramp(&output.id, original)?
+            }
+            _ => backend.reset(&output.id)?,
+        }
+    }
+    Ok(outputs.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owner_running_only_for_another_live_instance() {
+        let mut marker = DirtyMarker::new("mock", HashMap::new());
+        // 自己写的标记（或 pid 被本进程复用）照常还原
+        assert!(!marker.owner_running());
+        marker.pid = u32::MAX;
+        assert!(!marker.owner_running());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn recognizes_live_process_of_this_binary() {
+        assert!(is_this_binary(std::process::id()));
+        let mut other = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+        let running = is_this_binary(other.id());
+        let _ = other.kill();
+        let _ = other.wait();
+        assert!(!running);
+    }
+}
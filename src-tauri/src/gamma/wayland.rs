@@ -0,0 +1,356 @@
+// Wayland 后端：wlr-gamma-control 协议。合成器在 gamma control 对象销毁时会还原曲线，
+// 所以滤镜生效期间必须一直持有该对象，reset 时再销毁。
+use super::{GammaBackend, GammaOutput, GammaRamp, OutputBounds};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::os::fd::AsFd;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use wayland_client::protocol::{wl_output, wl_registry};
+use wayland_client::{delegate_noop, Connection, Dispatch, EventQueue, Proxy, QueueHandle, WEnum};
+use wayland_protocols_wlr::gamma_control::v1::client::{
+    zwlr_gamma_control_manager_v1::ZwlrGammaControlManagerV1,
+    zwlr_gamma_control_v1::{self, ZwlrGammaControlV1},
+};
+
+static RAMP_FILE_SEQ: AtomicU64 = AtomicU64::new(0);
+
+struct OutputEntry {
+    global_name: u32,
+    output: wl_output::WlOutput,
+    name: Option<String>,
+    /// 合成器布局坐标（逻辑像素）、整数缩放和当前模式的物理分辨率
+    x: i32,
+    y: i32,
+    scale: i32,
+    mode: Option<(u32, u32)>,
+    control: Option<ZwlrGammaControlV1>,
+    ramp_size: usize,
+    /// 已申请过一次 control 查询长度；被占用的输出也不每次重试，
+    /// 要等 registry 重新通告（重新插拔）时作为新条目再查
+    probed: bool,
+    failed: bool,
+}
+
+#[derive(Default)]
+struct WaylandState {
+    manager: Option<ZwlrGammaControlManagerV1>,
+    outputs: Vec<OutputEntry>,
+}
+
+impl WaylandState {
+    fn entry_mut(&mut self, global_name: u32) -> Option<&mut OutputEntry> {
+        self.outputs
+            .iter_mut()
+            .find(|entry| entry.global_name == global_name)
+    }
+}
+
+pub struct WaylandBackend {
+    conn: Connection,
+    queue: EventQueue<WaylandState>,
+    state: WaylandState,
+}
+
+impl WaylandBackend {
+    pub fn connect() -> Result<Self, String> {
+        let conn = Connection::connect_to_env().map_err(|err| err.to_string())?;
+        let mut queue = conn.new_event_queue();
+        let qh = queue.handle();
+        conn.display().get_registry(&qh, ());
+        let mut state = WaylandState::default();
+        // 第一次拿到全局对象，第二次拿到 wl_output 的 name 事件
+        queue.roundtrip(&mut state).map_err(|err| err.to_string())?;
+        queue.roundtrip(&mut state).map_err(|err| err.to_string())?;
+        if state.manager.is_none() {
+            return Err("合成器不支持 wlr-gamma-control 协议".into());
+        }
+        Ok(Self { conn, queue, state })
+    }
+
+    fn roundtrip(&mut self) -> Result<(), String> {
+        self.queue
+            .roundtrip(&mut self.state)
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+
+    /// 换算成物理像素，与 tauri 显示器的坐标对应
+    fn output_bounds(entry: &OutputEntry) -> Option<OutputBounds> {
+        let (width, height) = entry.mode?;
+        Some(OutputBounds {
+            x: entry.x * entry.scale,
+            y: entry.y * entry.scale,
+            width,
+            height,
+        })
+    }
+
+    fn output_id(entry: &OutputEntry) -> String {
+        entry
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("wl_output-{}", entry.global_name))
+    }
+
+    fn global_name_of(&self, output: &str) -> Result<u32, String> {
+        self.state
+            .outputs
+            .iter()
+            .find(|entry| Self::output_id(entry) == output)
+            .map(|entry| entry.global_name)
+            .ok_or_else(|| format!("未找到显示输出: {}", output))
+    }
+
+    /// 确保输出持有 gamma control，并等待合成器告知 gamma 表长度
+    fn ensure_control(&mut self, global_name: u32) -> Result<(), String> {
+        let qh = self.queue.handle();
+        let manager = self
+            .state
+            .manager
+            .clone()
+            .ok_or("合成器不支持 wlr-gamma-control 协议")?;
+        let entry = self.state.entry_mut(global_name).ok_or("显示输出已移除")?;
+        if entry.control.is_some() {
+            return Ok(());
+        }
+        entry.failed = false;
+        entry.control = Some(manager.get_gamma_control(&entry.output, &qh, global_name));
+        self.roundtrip()?;
+        let entry = self.state.entry_mut(global_name).ok_or("显示输出已移除")?;
+        if entry.failed || entry.control.is_none() {
+            return Err("gamma control 被其他程序占用".into());
+        }
+        Ok(())
+    }
+
+    fn release_control(&mut self, global_name: u32) {
+        if let Some(entry) = self.state.entry_mut(global_name) {
+            if let Some(control) = entry.control.take() {
+                control.destroy();
+            }
+        }
+        let _ = self.conn.flush();
+    }
+}
+
+/// 合成器从 fd 当前位置读取 r/g/b 三段原生字节序的 u16
+fn write_ramp_file(ramp: &GammaRamp) -> Result<File, String> {
+    let dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    let path = dir.join(format!(
+        "huyanba-gamma-{}-{}",
+        std::process::id(),
+        RAMP_FILE_SEQ.fetch_add(1, Ordering::SeqCst)
+    ));
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .map_err(|err| err.to_string())?;
+    let _ = fs::remove_file(&path);
+    let mut bytes = Vec::with_capacity(ramp.len() * 6);
+    for channel in [&ramp.red, &ramp.green, &ramp.blue] {
+        for value in channel.iter() {
+            bytes.extend_from_slice(&value.to_ne_bytes());
+        }
+    }
+    file.write_all(&bytes).map_err(|err| err.to_string())?;
+    file.seek(SeekFrom::Start(0))
+        .map_err(|err| err.to_string())?;
+    Ok(file)
+}
+
+impl Drop for WaylandBackend {
+    fn drop(&mut self) {
+        for entry in self.state.outputs.iter_mut() {
+            if let Some(control) = entry.control.take() {
+                control.destroy();
+            }
+        }
+        let _ = self.conn.flush();
+    }
+}
+
+impl GammaBackend for WaylandBackend {
+    fn name(&self) -> &'static str {
+        "wayland"
+    }
+
+    fn outputs(&mut self) -> Result<Vec<GammaOutput>, String> {
+        self.roundtrip()?;
+        let pending: Vec<u32> = self
+            .state
+            .outputs
+            .iter()
+            .filter(|entry| !entry.probed)
+            .map(|entry| entry.global_name)
+            .collect();
+        // 只为查询长度临时申请的 control 用完即释放，避免占住其他调色工具
+        for global_name in pending {
+            if let Some(entry) = self.state.entry_mut(global_name) {
+                entry.probed = true;
+            }
+            if self.ensure_control(global_name).is_ok() {
+                self.release_control(global_name);
+            }
+        }
+        Ok(self
+            .state
+            .outputs
+            .iter()
+            .filter(|entry| entry.ramp_size > 0)
+            .map(|entry| GammaOutput {
+                id: Self::output_id(entry),
+                ramp_size: entry.ramp_size,
+                bounds: Self::output_bounds(entry),
+            })
+            .collect())
+    }
+
+    fn get_ramp(&mut self, _output: &str) -> Result<GammaRamp, String> {
+        Err("Wayland 不支持读取 gamma 曲线".into())
+    }
+
+    fn set_ramp(&mut self, output: &str, ramp: &GammaRamp) -> Result<(), String> {
+        let global_name = self.global_name_of(output)?;
+        self.ensure_control(global_name)?;
+        let entry = self.state.entry_mut(global_name).ok_or("显示输出已移除")?;
+        if ramp.len() != entry.ramp_size {
+            return Err(format!("gamma 表长度不匹配: {}", ramp.len()));
+        }
+        let file = write_ramp_file(ramp)?;
+        if let Some(control) = entry.control.as_ref() {
+            control.set_gamma(file.as_fd());
+        }
+        self.conn.flush().map_err(|err| err.to_string())
+    }
+
+    fn reset(&mut self, output: &str) -> Result<(), String> {
+        let global_name = self.global_name_of(output)?;
+        self.release_control(global_name);
+        Ok(())
+    }
+}
+
+impl Dispatch<wl_registry::WlRegistry, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        registry: &wl_registry::WlRegistry,
+        event: wl_registry::Event,
+        _data: &(),
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_registry::Event::Global {
+                name,
+                interface,
+                version,
+            } => {
+                if interface == wl_output::WlOutput::interface().name {
+                    let output =
+                        registry.bind::<wl_output::WlOutput, _, _>(name, version.min(4), qh, name);
+                    state.outputs.push(OutputEntry {
+                        global_name: name,
+                        output,
+                        name: None,
+                        x: 0,
+                        y: 0,
+                        scale: 1,
+                        mode: None,
+                        control: None,
+                        ramp_size: 0,
+                        probed: false,
+                        failed: false,
+                    });
+                } else if interface == ZwlrGammaControlManagerV1::interface().name {
+                    state.manager =
+                        Some(registry.bind::<ZwlrGammaControlManagerV1, _, _>(name, 1, qh, ()));
+                }
+            }
+            // 拔掉的输出要销毁 control 并释放 wl_output，否则协议对象一直留在合成器里
+            wl_registry::Event::GlobalRemove { name } => {
+                let Some(index) = state
+                    .outputs
+                    .iter()
+                    .position(|entry| entry.global_name == name)
+                else {
+                    return;
+                };
+                let entry = state.outputs.remove(index);
+                if let Some(control) = entry.control {
+                    control.destroy();
+                }
+                // release 从 wl_output 第 3 版起才有
+                if entry.output.version() >= 3 {
+                    entry.output.release();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<wl_output::WlOutput, u32> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _output: &wl_output::WlOutput,
+        event: wl_output::Event,
+        global_name: &u32,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let Some(entry) = state.entry_mut(*global_name) else {
+            return;
+        };
+        match event {
+            wl_output::Event::Name { name } => entry.name = Some(name),
+            wl_output::Event::Geometry { x, y, .. } => {
+                entry.x = x;
+                entry.y = y;
+            }
+            wl_output::Event::Mode {
+                flags: WEnum::Value(flags),
+                width,
+                height,
+                ..
+            } if flags.contains(wl_output::Mode::Current) => {
+                entry.mode = Some((width.max(0) as u32, height.max(0) as u32));
+            }
+            wl_output::Event::Scale { factor } => entry.scale = factor.max(1),
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ZwlrGammaControlV1, u32> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _control: &ZwlrGammaControlV1,
+        event: zwlr_gamma_control_v1::Event,
+        global_name: &u32,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let Some(entry) = state.entry_mut(*global_name) else {
+            return;
+        };
+        match event {
+            zwlr_gamma_control_v1::Event::GammaSize { size } => {
+                entry.ramp_size = size as usize;
+            }
+            zwlr_gamma_control_v1::Event::Failed => {
+                entry.failed = true;
+                if let Some(control) = entry.control.take() {
+                    control.destroy();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+delegate_noop!(WaylandState: ZwlrGammaControlManagerV1);
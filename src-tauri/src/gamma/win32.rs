@@ -0,0 +1,124 @@
+// Win32 GDI 后端：按显示器设备名创建 DC 后读写 gamma 表
+use super::{GammaBackend, GammaOutput, GammaRamp, OutputBounds, DEFAULT_RAMP_SIZE};
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{BOOL, LPARAM, RECT, TRUE};
+use windows::Win32::Graphics::Gdi::{
+    CreateDCW, DeleteDC, EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO,
+    MONITORINFOEXW,
+};
+use windows::Win32::UI::ColorSystem::{GetDeviceGammaRamp, SetDeviceGammaRamp};
+
+pub struct Win32Backend;
+
+unsafe extern "system" fn collect_monitor(
+    monitor: HMONITOR,
+    _hdc: HDC,
+    _rect: *mut RECT,
+    data: LPARAM,
+) -> BOOL {
+    let devices = &mut *(data.0 as *mut Vec<(String, OutputBounds)>);
+    let mut info = MONITORINFOEXW::default();
+    info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+    if GetMonitorInfoW(
+        monitor,
+        &mut info as *mut MONITORINFOEXW as *mut MONITORINFO,
+    )
+    .as_bool()
+    {
+        let len = info
+            .szDevice
+            .iter()
+            .position(|c| *c == 0)
+            .unwrap_or(info.szDevice.len());
+        let rect = info.monitorInfo.rcMonitor;
+        devices.push((
+            String::from_utf16_lossy(&info.szDevice[..len]),
+            OutputBounds {
+                x: rect.left,
+                y: rect.top,
+                width: (rect.right - rect.left).max(0) as u32,
+                height: (rect.bottom - rect.top).max(0) as u32,
+            },
+        ));
+    }
+    TRUE
+}
+
+fn with_device_dc<T>(device: &str, f: impl FnOnce(HDC) -> Result<T, String>) -> Result<T, String> {
+    let wide: Vec<u16> = device.encode_utf16().chain(std::iter::once(0)).collect();
+    unsafe {
+        let hdc = CreateDCW(
+            PCWSTR(wide.as_ptr()),
+            PCWSTR(wide.as_ptr()),
+            PCWSTR::null(),
+            None,
+        );
+        if hdc.0 == 0 {
+            return Err("无法获取显示设备句柄".into());
+        }
+        let result = f(hdc);
+        let _ = DeleteDC(hdc);
+        result
+    }
+}
+
+impl GammaBackend for Win32Backend {
+    fn name(&self) -> &'static str {
+        "win32"
+    }
+
+    fn outputs(&mut self) -> Result<Vec<GammaOutput>, String> {
+        let mut devices: Vec<(String, OutputBounds)> = Vec::new();
+        let ok = unsafe {
+            EnumDisplayMonitors(
+                HDC(0),
+                None,
+                Some(collect_monitor),
+                LPARAM(&mut devices as *mut Vec<(String, OutputBounds)> as isize),
+            )
+        };
+        if !ok.as_bool() {
+            return Err("枚举显示器失败".into());
+        }
+        Ok(devices
+            .into_iter()
+            .map(|(id, bounds)| GammaOutput {
+                id,
+                ramp_size: DEFAULT_RAMP_SIZE,
+                bounds: Some(bounds),
+            })
+            .collect())
+    }
+
+    fn get_ramp(&mut self, output: &str) -> Result<GammaRamp, String> {
+        with_device_dc(output, |hdc| {
+            let mut raw = [0u16; DEFAULT_RAMP_SIZE * 3];
+            let ok = unsafe { GetDeviceGammaRamp(hdc, raw.as_mut_ptr() as *mut _).as_bool() };
+            if !ok {
+                return Err("读取色温失败".into());
+            }
+            Ok(GammaRamp {
+                red: raw[..DEFAULT_RAMP_SIZE].to_vec(),
+                green: raw[DEFAULT_RAMP_SIZE..DEFAULT_RAMP_SIZE * 2].to_vec(),
+                blue: raw[DEFAULT_RAMP_SIZE * 2..].to_vec(),
+            })
+        })
+    }
+
+    fn set_ramp(&mut self, output: &str, ramp: &GammaRamp) -> Result<(), String> {
+        if ramp.len() != DEFAULT_RAMP_SIZE {
+            return Err(format!("gamma 表长度不匹配: {}", ramp.len()));
+        }
+        let mut raw = [0u16; DEFAULT_RAMP_SIZE * 3];
+        raw[..DEFAULT_RAMP_SIZE].copy_from_slice(&ramp.red);
+        raw[DEFAULT_RAMP_SIZE..DEFAULT_RAMP_SIZE * 2].copy_from_slice(&ramp.green);
+        raw[DEFAULT_RAMP_SIZE * 2..].copy_from_slice(&ramp.blue);
+        with_device_dc(output, |hdc| {
+            let ok = unsafe { SetDeviceGammaRamp(hdc, raw.as_ptr() as *const _).as_bool() };
+            if !ok {
+                return Err("设置色温失败".into());
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,152 @@
+// X11 RandR 后端：按 CRTC 读写 gamma，输出以 RandR 接口名（如 HDMI-1）标识；
+// GTK 下 tauri 的 monitor.name() 是型号，由 monitors.rs 按位置与界面显示器配对
+use super::{GammaBackend, GammaOutput, GammaRamp, OutputBounds};
+use std::ffi::CStr;
+use std::ptr;
+use x11_dl::xlib::{Display, Xlib};
+use x11_dl::xrandr::{RRCrtc, RR_Connected, Xrandr};
+
+pub struct X11Backend {
+    xlib: Xlib,
+    xrandr: Xrandr,
+    display: *mut Display,
+    root: u64,
+}
+
+// Display 指针只在 GammaState 的互斥锁内使用
+unsafe impl Send for X11Backend {}
+
+impl X11Backend {
+    pub fn open() -> Result<Self, String> {
+        let xlib = Xlib::open().map_err(|err| err.to_string())?;
+        let xrandr = Xrandr::open().map_err(|err| err.to_string())?;
+        let display = unsafe { (xlib.XOpenDisplay)(ptr::null()) };
+        if display.is_null() {
+            return Err("无法连接 X11 显示服务".into());
+        }
+        let root = unsafe { (xlib.XDefaultRootWindow)(display) };
+        Ok(Self {
+            xlib,
+            xrandr,
+            display,
+            root,
+        })
+    }
+
+    /// 已连接且点亮的输出、其 CRTC 及 CRTC 在屏幕上的区域
+    fn connected_crtcs(&self) -> Result<Vec<(String, RRCrtc, Option<OutputBounds>)>, String> {
+        let mut result = Vec::new();
+        unsafe {
+            let resources = (self.xrandr.XRRGetScreenResourcesCurrent)(self.display, self.root);
+            if resources.is_null() {
+                return Err("读取 RandR 屏幕资源失败".into());
+            }
+            let outputs = std::slice::from_raw_parts(
+                (*resources).outputs,
+                (*resources).noutput.max(0) as usize,
+            );
+            for output in outputs {
+                let info = (self.xrandr.XRRGetOutputInfo)(self.display, resources, *output);
+                if info.is_null() {
+                    continue;
+                }
+                if (*info).connection as i32 == RR_Connected && (*info).crtc != 0 {
+                    let name = CStr::from_ptr((*info).name).to_string_lossy().to_string();
+                    let crtc = (*info).crtc;
+                    let crtc_info = (self.xrandr.XRRGetCrtcInfo)(self.display, resources, crtc);
+                    let bounds = (!crtc_info.is_null()).then(|| OutputBounds {
+                        x: (*crtc_info).x,
+                        y: (*crtc_info).y,
+                        width: (*crtc_info).width,
+                        height: (*crtc_info).height,
+                    });
+                    if !crtc_info.is_null() {
+                        (self.xrandr.XRRFreeCrtcInfo)(crtc_info);
+                    }
+                    result.push((name, crtc, bounds));
+                }
+                (self.xrandr.XRRFreeOutputInfo)(info);
+            }
+            (self.xrandr.XRRFreeScreenResources)(resources);
+        }
+        Ok(result)
+    }
+
+    fn crtc_for(&self, output: &str) -> Result<RRCrtc, String> {
+        self.connected_crtcs()?
+            .into_iter()
+            .find(|(name, _, _)| name == output)
+            .map(|(_, crtc, _)| crtc)
+            .ok_or_else(|| format!("未找到显示输出: {}", output))
+    }
+}
+
+impl Drop for X11Backend {
+    fn drop(&mut self) {
+        unsafe {
+            (self.xlib.XCloseDisplay)(self.display);
+        }
+    }
+}
+
+impl GammaBackend for X11Backend {
+    fn name(&self) -> &'static str {
+        "x11"
+    }
+
+    fn outputs(&mut self) -> Result<Vec<GammaOutput>, String> {
+        let crtcs = self.connected_crtcs()?;
+        Ok(crtcs
+            .into_iter()
+            .map(|(id, crtc, bounds)| {
+                let size = unsafe { (self.xrandr.XRRGetCrtcGammaSize)(self.display, crtc) };
+                GammaOutput {
+                    id,
+                    ramp_size: size.max(0) as usize,
+                    bounds,
+                }
+            })
+            .filter(|output| output.ramp_size > 0)
+            .collect())
+    }
+
+    fn get_ramp(&mut self, output: &str) -> Result<GammaRamp, String> {
+        let crtc = self.crtc_for(output)?;
+        unsafe {
+            let gamma = (self.xrandr.XRRGetCrtcGamma)(self.display, crtc);
+            if gamma.is_null() {
+                return Err("读取色温失败".into());
+            }
+            let size = (*gamma).size.max(0) as usize;
+            let ramp = GammaRamp {
+                red: std::slice::from_raw_parts((*gamma).red, size).to_vec(),
+                green: std::slice::from_raw_parts((*gamma).green, size).to_vec(),
+                blue: std::slice::from_raw_parts((*gamma).blue, size).to_vec(),
+            };
+            (self.xrandr.XRRFreeGamma)(gamma);
+            Ok(ramp)
+        }
+    }
+
+    fn set_ramp(&mut self, output: &str, ramp: &GammaRamp) -> Result<(), String> {
+        let crtc = self.crtc_for(output)?;
+        unsafe {
+            let size = (self.xrandr.XRRGetCrtcGammaSize)(self.display, crtc);
+            if size <= 0 || size as usize != ramp.len() {
+                return Err(format!("gamma 表长度不匹配: {}", ramp.len()));
+            }
+            let gamma = (self.xrandr.XRRAllocGamma)(size);
+            if gamma.is_null() {
+                return Err("设置色温失败".into());
+            }
+            let len = ramp.len();
+            std::slice::from_raw_parts_mut((*gamma).red, len).copy_from_slice(&ramp.red);
+            std::slice::from_raw_parts_mut((*gamma).green, len).copy_from_slice(&ramp.green);
+            std::slice::from_raw_parts_mut((*gamma).blue, len).copy_from_slice(&ramp.blue);
+            (self.xrandr.XRRSetCrtcGamma)(self.display, crtc, gamma);
+            (self.xrandr.XRRFreeGamma)(gamma);
+            (self.xlib.XSync)(self.display, 0);
+        }
+        Ok(())
+    }
+}
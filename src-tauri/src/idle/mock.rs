@@ -0,0 +1,25 @@
+// 测试用空闲来源：由测试直接设定空闲时长
+use super::IdleProvider;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone, Default)]
+pub struct MockIdle {
+    idle: Arc<Mutex<Option<Duration>>>,
+}
+
+impl MockIdle {
+    pub fn set(&self, idle: Option<Duration>) {
+        *self.idle.lock().unwrap() = idle;
+    }
+}
+
+impl IdleProvider for MockIdle {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn idle_time(&mut self) -> Option<Duration> {
+        *self.idle.lock().unwrap()
+    }
+}
@@ -0,0 +1,50 @@
+// 用户空闲时长：Windows 走 GetLastInputInfo，Linux 走 Wayland ext-idle-notify 或 X11 XScreenSaver
+use std::time::Duration;
+
+#[cfg(test)]
+pub mod mock;
+#[cfg(all(target_os = "linux", feature = "wayland"))]
+mod wayland;
+#[cfg(windows)]
+mod win32;
+#[cfg(all(target_os = "linux", feature = "x11"))]
+mod x11;
+
+pub trait IdleProvider: Send {
+    fn name(&self) -> &'static str;
+    /// 距最后一次键鼠输入的时长；平台不支持或查询失败时返回 None
+    fn idle_time(&mut self) -> Option<Duration>;
+}
+
+struct UnsupportedProvider;
+
+impl IdleProvider for UnsupportedProvider {
+    fn name(&self) -> &'static str {
+        "unsupported"
+    }
+
+    fn idle_time(&mut self) -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(windows)]
+pub fn default_provider() -> Box<dyn IdleProvider> {
+    Box::new(win32::Win32Idle)
+}
+
+#[cfg(not(windows))]
+pub fn default_provider() -> Box<dyn IdleProvider> {
+    // XWayland 只能看到发给 X 客户端的输入，Wayland 会话优先用合成器的空闲通知
+    #[cfg(all(target_os = "linux", feature = "wayland"))]
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        if let Ok(provider) = wayland::WaylandIdle::connect() {
+            return Box::new(provider);
+        }
+    }
+    #[cfg(all(target_os = "linux", feature = "x11"))]
+    if let Ok(provider) = x11::X11Idle::open() {
+        return Box::new(provider);
+    }
+    Box::new(UnsupportedProvider)
+}
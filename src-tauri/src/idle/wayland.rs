@@ -0,0 +1,109 @@
+// Wayland：ext-idle-notify 只在超过固定时长时通知一次，空闲时长按通知时刻倒推
+use super::IdleProvider;
+use std::time::{Duration, Instant};
+use wayland_client::protocol::{wl_registry, wl_seat};
+use wayland_client::{delegate_noop, Connection, Dispatch, EventQueue, Proxy, QueueHandle};
+use wayland_protocols::ext::idle_notify::v1::client::{
+    ext_idle_notification_v1::{self, ExtIdleNotificationV1},
+    ext_idle_notifier_v1::ExtIdleNotifierV1,
+};
+
+/// 通知粒度：空闲不足该时长时视为活跃
+const NOTIFY_AFTER: Duration = Duration::from_secs(10);
+
+#[derive(Default)]
+struct IdleState {
+    seat: Option<wl_seat::WlSeat>,
+    notifier: Option<ExtIdleNotifierV1>,
+    idle_since: Option<Instant>,
+}
+
+pub struct WaylandIdle {
+    queue: EventQueue<IdleState>,
+    state: IdleState,
+    _notification: ExtIdleNotificationV1,
+}
+
+impl WaylandIdle {
+    pub fn connect() -> Result<Self, String> {
+        let conn = Connection::connect_to_env().map_err(|err| err.to_string())?;
+        let mut queue = conn.new_event_queue();
+        let qh = queue.handle();
+        conn.display().get_registry(&qh, ());
+        let mut state = IdleState::default();
+        queue.roundtrip(&mut state).map_err(|err| err.to_string())?;
+        let (Some(seat), Some(notifier)) = (state.seat.clone(), state.notifier.clone()) else {
+            return Err("合成器不支持 ext-idle-notify 协议".into());
+        };
+        let notification =
+            notifier.get_idle_notification(NOTIFY_AFTER.as_millis() as u32, &seat, &qh, ());
+        queue.roundtrip(&mut state).map_err(|err| err.to_string())?;
+        Ok(Self {
+            queue,
+            state,
+            _notification: notification,
+        })
+    }
+}
+
+impl IdleProvider for WaylandIdle {
+    fn name(&self) -> &'static str {
+        "wayland"
+    }
+
+    fn idle_time(&mut self) -> Option<Duration> {
+        self.queue.roundtrip(&mut self.state).ok()?;
+        Some(
+            self.state
+                .idle_since
+                .map(|since| since.elapsed())
+                .unwrap_or_default(),
+        )
+    }
+}
+
+impl Dispatch<wl_registry::WlRegistry, ()> for IdleState {
+    fn event(
+        state: &mut Self,
+        registry: &wl_registry::WlRegistry,
+        event: wl_registry::Event,
+        _data: &(),
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        if let wl_registry::Event::Global {
+            name,
+            interface,
+            version,
+        } = event
+        {
+            if interface == wl_seat::WlSeat::interface().name && state.seat.is_none() {
+                state.seat = Some(registry.bind(name, version.min(7), qh, ()));
+            } else if interface == ExtIdleNotifierV1::interface().name {
+                state.notifier = Some(registry.bind(name, 1, qh, ()));
+            }
+        }
+    }
+}
+
+impl Dispatch<ExtIdleNotificationV1, ()> for IdleState {
+    fn event(
+        state: &mut Self,
+        _notification: &ExtIdleNotificationV1,
+        event: ext_idle_notification_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            ext_idle_notification_v1::Event::Idled => {
+                state.idle_since = Instant::now().checked_sub(NOTIFY_AFTER);
+            }
+            ext_idle_notification_v1::Event::Resumed => state.idle_since = None,
+            _ => {}
+        }
+    }
+}
+
+delegate_noop!(IdleState: ignore wl_seat::WlSeat);
+delegate_noop!(IdleState: ExtIdleNotifierV1);
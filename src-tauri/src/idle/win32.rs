@@ -0,0 +1,28 @@
+// Win32：GetLastInputInfo 返回最后一次输入时的开机毫秒数
+use super::IdleProvider;
+use std::time::Duration;
+use windows::Win32::System::SystemInformation::GetTickCount;
+use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+pub struct Win32Idle;
+
+impl IdleProvider for Win32Idle {
+    fn name(&self) -> &'static str {
+        "win32"
+    }
+
+    fn idle_time(&mut self) -> Option<Duration> {
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        unsafe {
+            if !GetLastInputInfo(&mut info).as_bool() {
+                return None;
+            }
+            // 两者都是 32 位计数，约 49.7 天回绕一次
+            let elapsed = GetTickCount().wrapping_sub(info.dwTime);
+            Some(Duration::from_millis(elapsed as u64))
+        }
+    }
+}
@@ -0,0 +1,75 @@
+// X11：MIT-SCREEN-SAVER 扩展直接给出空闲毫秒数
+use super::IdleProvider;
+use std::os::raw::c_int;
+use std::ptr;
+use std::time::Duration;
+use x11_dl::xlib::{Display, Xlib};
+use x11_dl::xss::{XScreenSaverInfo, Xss};
+
+pub struct X11Idle {
+    xlib: Xlib,
+    xss: Xss,
+    display: *mut Display,
+    root: u64,
+    info: *mut XScreenSaverInfo,
+}
+
+// Display 指针只在休息调度线程内使用
+unsafe impl Send for X11Idle {}
+
+impl X11Idle {
+    pub fn open() -> Result<Self, String> {
+        let xlib = Xlib::open().map_err(|err| err.to_string())?;
+        let xss = Xss::open().map_err(|err| err.to_string())?;
+        unsafe {
+            let display = (xlib.XOpenDisplay)(ptr::null());
+            if display.is_null() {
+                return Err("无法连接 X11 显示服务".into());
+            }
+            let (mut event_base, mut error_base): (c_int, c_int) = (0, 0);
+            if (xss.XScreenSaverQueryExtension)(display, &mut event_base, &mut error_base) == 0 {
+                (xlib.XCloseDisplay)(display);
+                return Err("X11 不支持 MIT-SCREEN-SAVER 扩展".into());
+            }
+            let info = (xss.XScreenSaverAllocInfo)();
+            if info.is_null() {
+                (xlib.XCloseDisplay)(display);
+                return Err("分配 XScreenSaverInfo 失败".into());
+            }
+            let root = (xlib.XDefaultRootWindow)(display);
+            Ok(Self {
+                xlib,
+                xss,
+                display,
+                root,
+                info,
+            })
+        }
+    }
+}
+
+impl Drop for X11Idle {
+    fn drop(&mut self) {
+        unsafe {
+            (self.xlib.XFree)(self.info.cast());
+            (self.xlib.XCloseDisplay)(self.display);
+        }
+    }
+}
+
+impl IdleProvider for X11Idle {
+    fn name(&self) -> &'static str {
+        "x11"
+    }
+
+    fn idle_time(&mut self) -> Option<Duration> {
+        unsafe {
+            if (self.xss.XScreenSaverQueryInfo)(self.display, self.root, self.info) == 0 {
+                return None;
+            }
+            // idle 是 c_ulong，32 位系统上是 u32
+            #[allow(clippy::useless_conversion)]
+            Some(Duration::from_millis(u64::from((*self.info).idle)))
+        }
+    }
+}
@@ -1,12 +1,45 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+pub mod cli;
+mod color;
+mod curve;
+mod filter;
+mod foreground;
+pub mod gamma;
+mod idle;
+mod lock_action;
+mod lock_layout;
+mod lock_session;
+mod monitors;
+mod notification;
+mod presets;
+mod profile;
+mod rest;
+mod settings;
+mod solar;
+mod strict;
+mod transition;
+
+use color::ColorAlgorithm;
+use curve::{ColorCurve, CurveState, KeyframeInput};
+use filter::{FilterSetting, FilterState};
+use gamma::{GammaState, ToneCurve};
+use lock_action::{LockAction, LockActionError};
+use lock_session::{LockSession, LockUpdate};
+use lock_layout::{LockWindow, MonitorInfo, WindowRect};
+use presets::{Preset, PresetState};
+use rest::{DeferRules, RestConfig, RestSnapshot, RestState};
+use settings::{Settings, SettingsState};
+use solar::{SolarSchedule, SolarState, SolarStatus};
+use strict::{StrictState, StrictStatus};
+use transition::TransitionState;
 use serde::{Deserialize, Serialize};
-use std::collections::{hash_map::DefaultHasher, HashSet};
+use std::collections::{hash_map::DefaultHasher, HashMap, HashSet};
 use std::fs;
 use std::hash::{Hash, Hasher};
 use std::io::{Cursor, Write};
 use std::path::{Path, PathBuf};
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicUsize, Ordering},
     Mutex,
 };
 use rand::Rng;
@@ -14,21 +47,20 @@ use reqwest::header::{
     HeaderMap, HeaderValue, ACCEPT, ACCEPT_LANGUAGE, REFERER, USER_AGENT,
 };
 use tauri::{
-    menu::MenuBuilder,
+    menu::{CheckMenuItemBuilder, Menu, MenuBuilder, SubmenuBuilder},
     path::BaseDirectory,
-    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
+    tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent},
     AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent,
 };
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use windows::Win32::Foundation::HWND;
-use windows::Win32::Graphics::Gdi::{GetDC, ReleaseDC};
-use windows::Win32::UI::ColorSystem::SetDeviceGammaRamp;
 use std::time::Instant;
 
 #[derive(Default)]
 struct LockState {
-    labels: Mutex<Vec<String>>,
-    last_update: Mutex<Option<LockUpdate>>,
+    windows: Mutex<Vec<LockWindow>>,
+    /// 窗口标签只增不复用，刚关闭的窗口可能还没销毁
+    next_index: AtomicUsize,
+    session: Mutex<Option<LockSession>>,
 }
 
 #[derive(Default)]
@@ -43,6 +75,9 @@ const WALLPAPER_BATCH_SIZE: usize = 10;
 const WALLPAPER_BATCH_INTERVAL_SECS: i64 = 7 * 24 * 60 * 60;
 const WALLPAPER_MIN_INTERVAL_SECS: i64 = 1;
 const WALLPAPER_MIN_WIDTH: u32 = 1920;
+const GAMMA_WATCH_INTERVAL_SECS: u64 = 3;
+const MICRO_LABEL: &str = "micro-break";
+const LOCK_REFOCUS_DELAY_MS: u64 = 100;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -65,14 +100,17 @@ struct WallpaperState {
 }
 
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
-struct LockUpdate {
-    time_text: String,
-    date_text: String,
-    rest_countdown: String,
-    rest_paused: bool,
-    allow_esc_exit: bool,
+struct DisplayOutput {
+    id: String,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    scale: f64,
+    gamma_supported: bool,
+    custom: Option<FilterSetting>,
 }
 
 #[tauri::command]
@@ -80,200 +118,1005 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-fn clamp(value: f64, min: f64, max: f64) -> f64 {
-    if value < min {
-        min
-    } else if value > max {
-        max
-    } else {
-        value
+fn restore_gamma(app: &AppHandle) {
+    if let Some(transition) = app.try_state::<TransitionState>() {
+        transition.stop();
+    }
+    if let Some(gamma) = app.try_state::<GammaState>() {
+        let _ = gamma.reset();
     }
 }
 
-fn temperature_to_rgb(temp: f64) -> (f64, f64, f64) {
-    let temp = clamp(temp, 1000.0, 40000.0) / 100.0;
-    let (mut r, mut g, mut b);
-    if temp <= 66.0 {
-        r = 255.0;
-        g = 99.4708025861 * temp.ln() - 161.1195681661;
-        b = if temp <= 19.0 {
-            0.0
-        } else {
-            138.5177312231 * (temp - 10.0).ln() - 305.0447927307
-        };
-    } else {
-        r = 329.698727446 * (temp - 60.0).powf(-0.1332047592);
-        g = 288.1221695283 * (temp - 60.0).powf(-0.0755148492);
-        b = 255.0;
+#[tauri::command]
+fn set_gamma(
+    app: AppHandle,
+    filter_enabled: bool,
+    strength: f64,
+    color_temp: f64,
+) -> Result<(), String> {
+    let setting = FilterSetting {
+        filter_enabled,
+        strength,
+        color_temp,
+    };
+    // 先保存，写入 gamma 失败（如显示器暂时不可用）也不丢设置
+    record_settings(&app, |settings| settings.filter = setting)?;
+    if let Err(err) = apply_global_filter(&app, setting) {
+        append_app_log(&app, &format!("应用全局滤镜失败: {}", err));
     }
+    Ok(())
+}
+
+/// 手动调节与自动调度共用的全局滤镜写入路径
+fn apply_global_filter(app: &AppHandle, setting: FilterSetting) -> Result<(), String> {
+    app.state::<FilterState>().set_global(setting);
+    transition::apply_filter(app, true)
+}
+
+#[tauri::command]
+fn get_solar_schedule(solar: tauri::State<'_, SolarState>) -> SolarStatus {
+    solar.status()
+}
+
+#[tauri::command]
+fn set_solar_location(
+    app: AppHandle,
+    solar: tauri::State<'_, SolarState>,
+    latitude: f64,
+    longitude: f64,
+) -> Result<SolarStatus, String> {
+    let mut schedule = solar.schedule();
+    schedule.latitude = latitude;
+    schedule.longitude = longitude;
+    solar.set_schedule(schedule)?;
+    solar.save(&ensure_config_dir(&app)?.join("solar_schedule.json"))?;
+    solar::tick(&app)?;
+    Ok(solar.status())
+}
+
+#[tauri::command]
+fn set_solar_schedule(
+    app: AppHandle,
+    solar: tauri::State<'_, SolarState>,
+    schedule: SolarSchedule,
+) -> Result<SolarStatus, String> {
+    solar.set_schedule(schedule)?;
+    solar.save(&ensure_config_dir(&app)?.join("solar_schedule.json"))?;
+    solar::tick(&app)?;
+    Ok(solar.status())
+}
+
+#[tauri::command]
+fn reset_gamma(
+    gamma: tauri::State<'_, GammaState>,
+    transition: tauri::State<'_, TransitionState>,
+) -> Result<(), String> {
+    transition.stop();
+    gamma.reset()
+}
 
-    r = clamp(r, 0.0, 255.0);
-    g = clamp(g, 0.0, 255.0);
-    b = clamp(b, 0.0, 255.0);
-    (r / 255.0, g / 255.0, b / 255.0)
+fn save_color_curve(app: &AppHandle) -> Result<ColorCurve, String> {
+    let curve = app.state::<CurveState>();
+    curve.save(&ensure_config_dir(app)?.join("color_curve.json"))?;
+    curve::tick(app)?;
+    Ok(curve.curve())
 }
 
-fn apply_gamma(mult_r: f64, mult_g: f64, mult_b: f64) -> Result<(), String> {
-    unsafe {
-        let hdc = GetDC(HWND(0));
-        if hdc.0 == 0 {
-            return Err("无法获取显示设备句柄".into());
+#[tauri::command]
+fn get_color_curve(curve: tauri::State<'_, CurveState>) -> ColorCurve {
+    curve.curve()
+}
+
+#[tauri::command]
+fn set_color_curve_enabled(
+    app: AppHandle,
+    curve: tauri::State<'_, CurveState>,
+    enabled: bool,
+) -> Result<ColorCurve, String> {
+    curve.update(|value| {
+        value.enabled = enabled;
+        Ok(())
+    })?;
+    if enabled {
+        app.state::<PresetState>().set_active(None);
+        record_settings(&app, |settings| settings.active_preset = None)?;
+        refresh_tray_menu(&app);
+    }
+    let saved = save_color_curve(&app)?;
+    if !enabled {
+        // 曲线写入的色温不再保留：交还日照调度，没开日照就回到保存的全局设置
+        let solar = app.state::<SolarState>();
+        if solar.schedule().enabled {
+            solar.forget_applied();
+            solar::tick(&app)?;
+        } else {
+            let filter = app.state::<SettingsState>().get().filter;
+            apply_global_filter(&app, filter)?;
+            // 界面滑块还停在曲线写入的值上，借 curve-filter 事件同步回来
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.emit("curve-filter", filter);
+            }
         }
+    }
+    Ok(saved)
+}
 
-        let mut ramp = [0u16; 256 * 3];
-        for i in 0..256 {
-            let base = i as f64 / 255.0;
-            ramp[i] = clamp(base * 65535.0 * mult_r, 0.0, 65535.0).round() as u16;
-            ramp[i + 256] = clamp(base * 65535.0 * mult_g, 0.0, 65535.0).round() as u16;
-            ramp[i + 512] = clamp(base * 65535.0 * mult_b, 0.0, 65535.0).round() as u16;
+#[tauri::command]
+fn list_presets(presets: tauri::State<'_, PresetState>) -> Vec<Preset> {
+    presets.list()
+}
+
+#[tauri::command]
+fn get_active_preset(presets: tauri::State<'_, PresetState>) -> Option<String> {
+    presets.active()
+}
+
+fn save_presets(app: &AppHandle) -> Result<Vec<Preset>, String> {
+    let presets = app.state::<PresetState>();
+    presets.save(&ensure_config_dir(app)?.join("presets.json"))?;
+    // 改名或删除当前预设时同步记录
+    let active = presets.active();
+    record_settings(app, |settings| settings.active_preset = active)?;
+    refresh_tray_menu(app);
+    Ok(presets.list())
+}
+
+#[tauri::command]
+fn create_preset(
+    app: AppHandle,
+    presets: tauri::State<'_, PresetState>,
+    preset: Preset,
+) -> Result<Vec<Preset>, String> {
+    presets.create(preset)?;
+    save_presets(&app)
+}
+
+#[tauri::command]
+fn update_preset(
+    app: AppHandle,
+    presets: tauri::State<'_, PresetState>,
+    name: String,
+    preset: Preset,
+) -> Result<Vec<Preset>, String> {
+    presets.update(&name, preset)?;
+    save_presets(&app)
+}
+
+#[tauri::command]
+fn delete_preset(
+    app: AppHandle,
+    presets: tauri::State<'_, PresetState>,
+    name: String,
+) -> Result<Vec<Preset>, String> {
+    presets.delete(&name)?;
+    save_presets(&app)
+}
+
+#[tauri::command]
+fn apply_preset(app: AppHandle, name: String) -> Result<Preset, String> {
+    apply_preset_by_name(&app, &name)
+}
+
+/// 界面与托盘共用：关闭时间曲线，按预设切换日照调度或直接写入白天设置
+/// 带休息节奏的预设同时改写工作/休息时长并立即生效
+fn apply_preset_by_name(app: &AppHandle, name: &str) -> Result<Preset, String> {
+    let preset = app.state::<PresetState>().find(name)?;
+    let dir = ensure_config_dir(app)?;
+    let curve = app.state::<CurveState>();
+    if curve.is_enabled() {
+        curve.update(|value| {
+            value.enabled = false;
+            Ok(())
+        })?;
+        curve.save(&dir.join("color_curve.json"))?;
+    }
+    let solar = app.state::<SolarState>();
+    let mut schedule = solar.schedule();
+    schedule.enabled = preset.follow_sun;
+    schedule.day = preset.day;
+    schedule.night = preset.night;
+    solar.set_schedule(schedule)?;
+    solar.save(&dir.join("solar_schedule.json"))?;
+    if preset.follow_sun {
+        solar::tick(app)?;
+    } else {
+        apply_global_filter(
+            app,
+            FilterSetting {
+                filter_enabled: true,
+                strength: preset.day.strength,
+                color_temp: preset.day.color_temp,
+            },
+        )?;
+    }
+    app.state::<PresetState>()
+        .set_active(Some(preset.name.clone()));
+    let settings = app.state::<SettingsState>().modify(|settings| {
+        settings.active_preset = Some(preset.name.clone());
+        if let Some(rest) = preset.rest {
+            settings.rest.work_minutes = rest.work_minutes;
+            settings.rest.rest_minutes = rest.rest_minutes;
+        }
+    })?;
+    if preset.rest.is_some() {
+        let config = RestConfig::from_settings(&settings);
+        if app.state::<RestState>().with(|rest| rest.configure(config))? {
+            rest::sync(app)?;
         }
+    }
+    refresh_tray_menu(app);
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit("preset-applied", preset.clone());
+    }
+    append_app_log(app, &format!("应用预设: {}", preset.name));
+    Ok(preset)
+}
 
-        let ok = SetDeviceGammaRamp(hdc, ramp.as_ptr() as *const _).as_bool();
-        ReleaseDC(HWND(0), hdc);
-        if !ok {
-            return Err("设置色温失败".into());
+fn build_tray_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let presets = app.state::<PresetState>();
+    let active = presets.active();
+    let mut submenu = SubmenuBuilder::new(app, "预设");
+    for preset in presets.list() {
+        let item = CheckMenuItemBuilder::new(&preset.name)
+            .id(format!("preset:{}", preset.name))
+            .checked(active.as_deref() == Some(preset.name.as_str()))
+            .build(app)?;
+        submenu = submenu.item(&item);
+    }
+    let submenu = submenu.build()?;
+    MenuBuilder::new(app)
+        .text("tray_show", "显示主界面")
+        .text("tray_hide", "隐藏到托盘")
+        .separator()
+        .item(&submenu)
+        .separator()
+        .text("tray_quit", "退出")
+        .build()
+}
+
+/// 预设增删或切换后重建托盘菜单，保持勾选状态一致
+fn refresh_tray_menu(app: &AppHandle) {
+    let Some(tray) = app.try_state::<TrayIcon>() else {
+        return;
+    };
+    match build_tray_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
         }
+        Err(err) => append_app_log(app, &format!("托盘菜单刷新失败: {}", err)),
+    }
+}
+
+#[tauri::command]
+fn add_curve_keyframe(
+    app: AppHandle,
+    curve: tauri::State<'_, CurveState>,
+    keyframe: KeyframeInput,
+) -> Result<ColorCurve, String> {
+    curve.update(|value| value.add(keyframe))?;
+    save_color_curve(&app)
+}
+
+#[tauri::command]
+fn update_curve_keyframe(
+    app: AppHandle,
+    curve: tauri::State<'_, CurveState>,
+    id: u32,
+    keyframe: KeyframeInput,
+) -> Result<ColorCurve, String> {
+    curve.update(|value| value.update(id, keyframe))?;
+    save_color_curve(&app)
+}
+
+#[tauri::command]
+fn delete_curve_keyframe(
+    app: AppHandle,
+    curve: tauri::State<'_, CurveState>,
+    id: u32,
+) -> Result<ColorCurve, String> {
+    curve.update(|value| value.remove(id))?;
+    save_color_curve(&app)
+}
+
+#[tauri::command]
+fn set_tone_curve(
+    app: AppHandle,
+    gamma: tauri::State<'_, GammaState>,
+    brightness: f64,
+    contrast: f64,
+    gamma_red: f64,
+    gamma_green: f64,
+    gamma_blue: f64,
+) -> Result<(), String> {
+    let tone = ToneCurve {
+        brightness,
+        contrast,
+        gamma_red,
+        gamma_green,
+        gamma_blue,
+    };
+    // 和 set_gamma 一样先保存，写入 gamma 失败只记日志
+    record_settings(&app, |settings| settings.tone = tone)?;
+    gamma.set_tone(tone)?;
+    if let Err(err) = transition::apply_filter(&app, true) {
+        append_app_log(&app, &format!("应用色调曲线失败: {}", err));
     }
     Ok(())
 }
 
 #[tauri::command]
-fn set_gamma(filter_enabled: bool, strength: f64, color_temp: f64) -> Result<(), String> {
-    if !filter_enabled {
-        return apply_gamma(1.0, 1.0, 1.0);
+fn set_color_algorithm(
+    app: AppHandle,
+    filter: tauri::State<'_, FilterState>,
+    algorithm: ColorAlgorithm,
+) -> Result<(), String> {
+    filter.set_algorithm(algorithm);
+    transition::apply_filter(&app, true)?;
+    record_settings(&app, |settings| settings.color_algorithm = algorithm)
+}
+
+#[tauri::command]
+fn set_transition_duration(
+    app: AppHandle,
+    transition: tauri::State<'_, TransitionState>,
+    duration_ms: u64,
+) -> Result<(), String> {
+    transition.set_duration_ms(duration_ms);
+    record_settings(&app, |settings| settings.transition_ms = duration_ms)
+}
+
+#[tauri::command]
+fn get_settings(settings: tauri::State<'_, SettingsState>) -> Settings {
+    settings.get()
+}
+
+/// 按字段合并界面传来的部分设置，校验通过后保存并立即生效
+#[tauri::command]
+fn update_settings(
+    app: AppHandle,
+    settings: tauri::State<'_, SettingsState>,
+    patch: serde_json::Value,
+) -> Result<Settings, String> {
+    let next = settings.get().merged(&patch)?;
+    replace_settings(&app, next)
+}
+
+/// 导出设置与自定义预设，按扩展名选择 JSON 或 TOML
+#[tauri::command]
+fn export_settings(app: AppHandle, path: String) -> Result<(), String> {
+    profile::export_file(Path::new(&path), &profile_stores(&app).bundle())?;
+    append_app_log(&app, &format!("导出配置: {}", path));
+    Ok(())
+}
+
+fn profile_stores(app: &AppHandle) -> profile::Stores<'_> {
+    profile::Stores {
+        settings: app.state::<SettingsState>().inner(),
+        presets: app.state::<PresetState>().inner(),
+        solar: app.state::<SolarState>().inner(),
+        curve: app.state::<CurveState>().inner(),
+        filter: app.state::<FilterState>().inner(),
     }
-    let (r, g, b) = temperature_to_rgb(color_temp);
-    let factor = clamp(strength / 100.0, 0.0, 1.0);
-    let mut mult_r = (1.0 - factor) + factor * r;
-    let mut mult_g = (1.0 - factor) + factor * g;
-    let mut mult_b = (1.0 - factor) + factor * b;
+}
 
-    // Greenish bias to avoid reddish tint and reduce blue light.
-    let green_boost = 0.08 * factor;
-    let red_cut = 0.18 * factor;
-    let blue_cut = 0.35 * factor;
-    mult_r = clamp(mult_r * (1.0 - red_cut), 0.0, 1.0);
-    mult_g = clamp(mult_g * (1.0 + green_boost), 0.0, 1.0);
-    mult_b = clamp(mult_b * (1.0 - blue_cut), 0.0, 1.0);
-    apply_gamma(mult_r, mult_g, mult_b)
+/// 整个文件校验通过才生效，自定义预设整体替换
+#[tauri::command]
+fn import_settings(app: AppHandle, path: String) -> Result<Settings, String> {
+    let bundle = profile::import_file(Path::new(&path))?;
+    // 被拒绝时什么都不能写，预设也要等检查通过再替换
+    check_strict_change(&app, &bundle.settings)?;
+    let dir = ensure_config_dir(&app)?;
+    let settings = profile_stores(&app).apply(&dir, bundle, |next| replace_settings(&app, next))?;
+    // 全局设置写入后再让曲线或日照调度接管
+    app.state::<CurveState>().forget_applied();
+    app.state::<SolarState>().forget_applied();
+    curve::tick(&app)?;
+    solar::tick(&app)?;
+    append_app_log(&app, &format!("导入配置: {}", path));
+    Ok(settings)
 }
 
+/// 恢复默认设置，保留自定义预设
 #[tauri::command]
-fn reset_gamma() -> Result<(), String> {
-    apply_gamma(1.0, 1.0, 1.0)
+fn reset_settings(app: AppHandle) -> Result<Settings, String> {
+    let settings = replace_settings(&app, Settings::default())?;
+    append_app_log(&app, "设置已恢复默认");
+    Ok(settings)
+}
+
+/// 严格锁屏期间不允许改动严格模式设置
+fn check_strict_change(app: &AppHandle, next: &Settings) -> Result<(), String> {
+    if next.rest.strict != app.state::<SettingsState>().get().rest.strict {
+        refuse_strict_exit(app, "修改严格模式设置")?;
+    }
+    Ok(())
+}
+
+fn replace_settings(app: &AppHandle, next: Settings) -> Result<Settings, String> {
+    check_strict_change(app, &next)?;
+    let settings = app.state::<SettingsState>();
+    settings.replace(next.clone())?;
+    settings.save()?;
+    load_settings_into_state(app, &next)?;
+    // 设置已保存，写入 gamma 失败只记日志，下次轮询或调度会再写
+    if let Err(err) = transition::apply_filter(app, true) {
+        append_app_log(app, &format!("应用设置到显示器失败: {}", err));
+    }
+    refresh_tray_menu(app);
+    Ok(next)
+}
+
+fn record_settings(app: &AppHandle, change: impl FnOnce(&mut Settings)) -> Result<(), String> {
+    app.state::<SettingsState>().modify(change).map(|_| ())
+}
+
+/// 把设置同步到各运行时状态，不直接写入 gamma
+fn load_settings_into_state(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+    app.state::<FilterState>().set_global(settings.filter);
+    app.state::<FilterState>()
+        .set_algorithm(settings.color_algorithm);
+    app.state::<TransitionState>()
+        .set_duration_ms(settings.transition_ms);
+    app.state::<GammaState>().set_tone(settings.tone)?;
+    let presets = app.state::<PresetState>();
+    let active = settings
+        .active_preset
+        .clone()
+        .filter(|name| presets.find(name).is_ok());
+    presets.set_active(active);
+    let config = RestConfig::from_settings(settings);
+    let rules = DeferRules::from_settings(settings);
+    let changed = app.state::<RestState>().with(|rest| {
+        rest.set_rules(rules);
+        rest.configure(config)
+    })?;
+    if changed {
+        rest::sync(app)?;
+    }
+    Ok(())
+}
+
+fn monitor_id(monitor: &MonitorInfo, index: usize) -> String {
+    monitor
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("monitor-{}", index))
 }
 
 #[tauri::command]
-async fn show_lock_windows(
-    app: tauri::AppHandle,
-    state: tauri::State<'_, LockState>,
-    end_at_ms: i64,
-    paused: bool,
-    paused_remaining: i64,
-    allow_esc: bool,
+fn list_display_outputs(
+    app: AppHandle,
+    gamma: tauri::State<'_, GammaState>,
+    filter: tauri::State<'_, FilterState>,
+) -> Result<Vec<DisplayOutput>, String> {
+    let outputs = gamma.outputs().unwrap_or_default();
+    let overrides = filter.overrides();
+    let monitors = app.available_monitors().map_err(|err| err.to_string())?;
+    let infos: Vec<MonitorInfo> = monitors.iter().map(MonitorInfo::from_monitor).collect();
+    let matched = monitors::match_outputs(&infos, &outputs);
+    // 单独设置按后端输出 id 保存，配不上后端输出的显示器不能单独调
+    Ok(infos
+        .into_iter()
+        .zip(matched)
+        .enumerate()
+        .map(|(index, (monitor, output))| {
+            let gamma_supported = output.is_some();
+            let id = output.unwrap_or_else(|| monitor_id(&monitor, index));
+            DisplayOutput {
+                x: monitor.x,
+                y: monitor.y,
+                width: monitor.width,
+                height: monitor.height,
+                scale: monitor.scale,
+                gamma_supported,
+                custom: overrides.get(&id).copied(),
+                id,
+            }
+        })
+        .collect())
+}
+
+/// 只接受后端当前能写的输出，取值范围与导入配置相同
+#[tauri::command]
+fn set_output_gamma(
+    app: AppHandle,
+    gamma: tauri::State<'_, GammaState>,
+    filter: tauri::State<'_, FilterState>,
+    output_id: String,
+    filter_enabled: bool,
+    strength: f64,
+    color_temp: f64,
+) -> Result<(), String> {
+    let setting = FilterSetting {
+        filter_enabled,
+        strength,
+        color_temp,
+    };
+    setting.validate()?;
+    if !gamma.outputs()?.iter().any(|output| output.id == output_id) {
+        return Err(format!("未找到显示输出: {}", output_id));
+    }
+    filter.set_output(&output_id, setting);
+    filter.save_overrides(&ensure_config_dir(&app)?.join("gamma_outputs.json"))?;
+    transition::apply_filter(&app, true)
+}
+
+#[tauri::command]
+fn clear_output_gamma(
+    app: AppHandle,
+    filter: tauri::State<'_, FilterState>,
+    output_id: String,
 ) -> Result<(), String> {
+    filter.clear_output(&output_id);
+    filter.save_overrides(&ensure_config_dir(&app)?.join("gamma_outputs.json"))?;
+    transition::apply_filter(&app, true)
+}
+
+/// 上次异常退出时屏幕可能还带着滤镜，启动时先按标记还原
+fn recover_gamma(app: &AppHandle, marker: PathBuf) {
+    let gamma = app.state::<GammaState>();
+    gamma.set_marker_path(marker);
+    match gamma.recover_from_marker() {
+        Ok(Some(count)) => append_app_log(
+            app,
+            &format!("检测到上次异常退出，已还原 gamma 输出数={}", count),
+        ),
+        Ok(None) => {}
+        Err(err) => append_app_log(app, &format!("还原上次 gamma 失败: {}", err)),
+    }
+}
+
+/// 轮询显示输出：热插拔后重新写入全局/单独设置；曲线被外部程序改写时恢复
+fn watch_gamma(app: AppHandle) {
+    let mut known: Vec<String> = Vec::new();
+    let mut drift_counts: HashMap<String, u64> = HashMap::new();
+    loop {
+        std::thread::sleep(Duration::from_secs(GAMMA_WATCH_INTERVAL_SECS));
+        let gamma = app.state::<GammaState>();
+        if let Ok(outputs) = gamma.outputs() {
+            let mut ids: Vec<String> = outputs.into_iter().map(|output| output.id).collect();
+            ids.sort();
+            if ids != known {
+                if !known.is_empty() {
+                    append_app_log(&app, &format!("显示输出变化: {:?} -> {:?}", known, ids));
+                    if let Err(err) = transition::apply_filter(&app, false) {
+                        append_app_log(&app, &format!("热插拔后重新应用色温失败: {}", err));
+                    }
+                }
+                known = ids;
+                continue;
+            }
+        }
+        match gamma.restore_drifted() {
+            Ok(drifts) => {
+                for drift in drifts {
+                    let count = drift_counts.entry(drift.output.clone()).or_insert(0);
+                    *count += 1;
+                    append_app_log(
+                        &app,
+                        &format!(
+                            "gamma 被外部改写，已恢复 output={} max_delta={} 累计={}",
+                            drift.output, drift.max_delta, count
+                        ),
+                    );
+                }
+            }
+            Err(err) => append_app_log(&app, &format!("gamma 看门狗恢复失败: {}", err)),
+        }
+    }
+}
+
+#[tauri::command]
+async fn show_lock_windows(app: tauri::AppHandle) -> Result<(), String> {
+    open_lock_windows(&app)
+}
+
+/// 休息调度与命令共用：每块屏幕一个锁屏窗口，已打开时按当前显示器补建、关闭或重新摆放，再置顶聚焦
+fn open_lock_windows(app: &AppHandle) -> Result<(), String> {
     let start = Instant::now();
-    let mut labels = state.labels.lock().map_err(|_| "锁状态被占用")?;
-    if !labels.is_empty() {
-        for label in labels.iter() {
-            if let Some(window) = app.get_webview_window(label) {
-                let _ = window.set_always_on_top(true);
-                let _ = window.show();
-                let _ = window.set_focus();
+    let state = app.state::<LockState>();
+    let mut windows = state.windows.lock().map_err(|_| "锁状态被占用")?;
+    // 倒计时与壁纸都由 lockscreen-update 推送，地址里只标明锁屏
+    let url = "index.html?lockscreen=1";
+    let monitors = current_monitors(app)?;
+    let overscan = app.state::<SettingsState>().get().rest.lock_overscan;
+    let opening = windows.is_empty();
+    if opening {
+        append_app_log(app, &format!("锁屏创建开始 monitors={}", monitors.len()));
+        // 锁屏期间滤镜渐隐，壁纸以原色显示
+        app.state::<TransitionState>().set_suspended(true);
+        let _ = transition::apply_filter(app, true);
+        let wallpaper = pick_lock_wallpaper(app).unwrap_or_else(|err| {
+            append_app_log(app, &format!("锁屏壁纸读取失败: {}", err));
+            None
+        });
+        if let Ok(mut session) = state.session.lock() {
+            *session = Some(LockSession::new(wallpaper));
+        }
+    } else {
+        windows.retain(|window| {
+            let alive = app.get_webview_window(&window.label).is_some();
+            if !alive {
+                append_app_log(app, &format!("锁屏窗口被关闭，重新创建 {}", window.label));
             }
+            alive
+        });
+    }
+
+    let plan = lock_layout::reconcile(&windows, &monitors);
+    if !opening && !plan.is_empty() {
+        append_app_log(
+            app,
+            &format!(
+                "显示器变化，调整锁屏 新建={} 关闭={} 重新摆放={}",
+                plan.create.len(),
+                plan.close.len(),
+                plan.reposition.len()
+            ),
+        );
+    }
+    for label in &plan.close {
+        if let Some(window) = app.get_webview_window(label) {
+            let _ = window.close();
         }
-        return Ok(());
+    }
+    windows.retain(|window| !plan.close.contains(&window.label));
+    for (label, monitor) in plan.reposition {
+        if let Some(window) = app.get_webview_window(&label) {
+            place_lock_window(&window, lock_layout::window_rect(&monitor, overscan));
+        }
+        if let Some(entry) = windows.iter_mut().find(|window| window.label == label) {
+            entry.monitor = monitor;
+        }
+    }
+    for monitor in plan.create {
+        let label = format!(
+            "lockscreen-{}",
+            state.next_index.fetch_add(1, Ordering::SeqCst)
+        );
+        build_lock_window(app, &label, lock_layout::window_rect(&monitor, overscan), url)?;
+        windows.push(LockWindow { label, monitor });
     }
 
-    let monitors = app
-        .available_monitors()
-        .map_err(|err| err.to_string())?;
-    append_app_log(&app, &format!("锁屏创建开始 monitors={}", monitors.len()));
-    for (index, monitor) in monitors.into_iter().enumerate() {
-        let label = format!("lockscreen-{}", index);
-        let position = monitor.position();
-        let size = monitor.size();
-        let scale = monitor.scale_factor();
-        let width = (size.width as f64 / scale).ceil() + 400.0;
-        let height = (size.height as f64 / scale).ceil() + 400.0;
-        let x = (position.x as f64 / scale).floor() - 200.0;
-        let y = (position.y as f64 / scale).floor() - 200.0;
-
-        let url = format!(
-            "index.html?lockscreen=1&end={}&paused={}&remaining={}&allowEsc={}",
-            end_at_ms,
-            if paused { 1 } else { 0 },
-            paused_remaining,
-            if allow_esc { 1 } else { 0 }
+    for entry in windows.iter() {
+        if let Some(window) = app.get_webview_window(&entry.label) {
+            let _ = window.set_always_on_top(true);
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+    if opening {
+        append_app_log(
+            app,
+            &format!("锁屏创建完成 labels={} elapsed_ms={}", windows.len(), start.elapsed().as_millis()),
         );
-        let window = WebviewWindowBuilder::new(&app, label.clone(), WebviewUrl::App(url.into()))
+    }
+    Ok(())
+}
+
+fn current_monitors(app: &AppHandle) -> Result<Vec<MonitorInfo>, String> {
+    Ok(app
+        .available_monitors()
+        .map_err(|err| err.to_string())?
+        .iter()
+        .map(MonitorInfo::from_monitor)
+        .collect())
+}
+
+/// 先隐藏着建好，按物理像素摆到目标屏幕后再显示
+fn build_lock_window(
+    app: &AppHandle,
+    label: &str,
+    rect: WindowRect,
+    url: &str,
+) -> Result<(), String> {
+    let window = WebviewWindowBuilder::new(app, label, WebviewUrl::App(url.into()))
         .decorations(false)
         .transparent(false)
         .resizable(false)
         .always_on_top(true)
         .skip_taskbar(true)
+        .visible(false)
+        .build()
+        .map_err(|err| err.to_string())?;
+
+    place_lock_window(&window, rect);
+    let _ = window.set_focus();
+    Ok(())
+}
+
+/// 先退出全屏再移动，否则窗口会留在原来的屏幕上
+fn place_lock_window(window: &tauri::WebviewWindow, rect: WindowRect) {
+    let _ = window.set_fullscreen(false);
+    let _ = window.set_position(tauri::PhysicalPosition::new(rect.x, rect.y));
+    let _ = window.set_size(tauri::PhysicalSize::new(rect.width, rect.height));
+    let _ = window.show();
+    let _ = window.set_fullscreen(true);
+}
+
+/// 锁屏期间由休息调度每个周期调用：显示器有变化或窗口被关掉就重新对齐，
+/// 严格模式下焦点被抢走也拉回
+fn check_lock_windows(app: &AppHandle, strict: bool) {
+    let Ok(monitors) = current_monitors(app) else {
+        return;
+    };
+    let (stale, focused) = match app.state::<LockState>().windows.lock() {
+        Ok(windows) => {
+            let alive: Vec<_> = windows
+                .iter()
+                .filter_map(|window| app.get_webview_window(&window.label))
+                .collect();
+            let stale = alive.len() != windows.len()
+                || !lock_layout::reconcile(&windows, &monitors).is_empty();
+            let focused = alive
+                .iter()
+                .any(|window| window.is_focused().unwrap_or(false));
+            (stale, focused)
+        }
+        Err(_) => return,
+    };
+    if !stale && (focused || !strict) {
+        return;
+    }
+    if let Err(err) = rest::sync(app) {
+        append_app_log(app, &format!("锁屏恢复失败: {}", err));
+    }
+}
+
+/// 严格模式下锁屏失焦时立刻抢回来，轮询只作兜底。稍等一下再看，
+/// 焦点只是在几块屏幕的锁屏窗口之间切换时不动
+fn reclaim_lock_focus(app: &AppHandle, label: String) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(LOCK_REFOCUS_DELAY_MS));
+        if !strict_lock_active(&app) {
+            return;
+        }
+        let focused = app
+            .webview_windows()
+            .iter()
+            .filter(|(label, _)| label.starts_with("lockscreen-"))
+            .any(|(_, window)| window.is_focused().unwrap_or(false));
+        if focused {
+            return;
+        }
+        if let Some(window) = app.get_webview_window(&label) {
+            let _ = window.set_always_on_top(true);
+            let _ = window.set_focus();
+        }
+    });
+}
+
+/// 严格模式的锁屏正在显示，此时不能随手结束休息或退出
+fn strict_lock_active(app: &AppHandle) -> bool {
+    app.state::<RestState>()
+        .snapshot()
+        .map(|snapshot| snapshot.strict && snapshot.is_locked())
+        .unwrap_or(false)
+}
+
+fn refuse_strict_exit(app: &AppHandle, what: &str) -> Result<(), String> {
+    if strict_lock_active(app) {
+        append_app_log(app, &format!("严格模式拒绝{}", what));
+        return Err("严格模式下只能用应急口令或当日次数提前结束休息".into());
+    }
+    Ok(())
+}
+
+/// 小憩提示：主显示器右下角的小窗，不抢焦点也不挡操作
+fn open_micro_overlay(app: &AppHandle, end_at_ms: i64) -> Result<(), String> {
+    if app.get_webview_window(MICRO_LABEL).is_some() {
+        return Ok(());
+    }
+    let monitor = app
+        .primary_monitor()
+        .map_err(|err| err.to_string())?
+        .ok_or("没有可用的显示器")?;
+    let scale = monitor.scale_factor();
+    let (width, height) = (340.0, 132.0);
+    let x = (monitor.position().x as f64 + monitor.size().width as f64) / scale - width - 24.0;
+    // 底部留出任务栏的高度
+    let y = (monitor.position().y as f64 + monitor.size().height as f64) / scale - height - 72.0;
+    let url = format!("index.html?microbreak=1&end={}", end_at_ms);
+    WebviewWindowBuilder::new(app, MICRO_LABEL, WebviewUrl::App(url.into()))
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .focused(false)
         .position(x, y)
         .inner_size(width, height)
         .build()
         .map_err(|err| err.to_string())?;
+    append_app_log(app, "小憩提示已显示");
+    Ok(())
+}
 
-        let _ = window.set_fullscreen(true);
-        let _ = window.set_focus();
-        labels.push(label);
+fn close_micro_overlay(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(MICRO_LABEL) {
+        window.close().map_err(|err| err.to_string())?;
     }
-
-    append_app_log(
-        &app,
-        &format!("锁屏创建完成 labels={} elapsed_ms={}", labels.len(), start.elapsed().as_millis()),
-    );
     Ok(())
 }
 
 #[tauri::command]
-fn hide_lock_windows(
-    app: tauri::AppHandle,
-    state: tauri::State<'_, LockState>,
-) -> Result<(), String> {
+fn end_micro_break(app: AppHandle) -> Result<RestSnapshot, String> {
+    rest::act(&app, |rest| rest.end_micro())
+}
+
+/// 和锁屏上的退出一样先结束本次休息，不然调度下一轮又会把锁屏打开
+#[tauri::command]
+fn hide_lock_windows(app: tauri::AppHandle) -> Result<(), String> {
+    refuse_strict_exit(&app, "关闭锁屏")?;
+    rest::act(&app, |rest| rest.end_rest())?;
+    close_lock_windows(&app)
+}
+
+fn close_lock_windows(app: &AppHandle) -> Result<(), String> {
     let start = Instant::now();
-    let mut labels = state.labels.lock().map_err(|_| "锁状态被占用")?;
-    append_app_log(&app, &format!("锁屏关闭开始 labels={}", labels.len()));
-    for label in labels.iter() {
-        if let Some(window) = app.get_webview_window(label) {
+    let state = app.state::<LockState>();
+    let mut windows = state.windows.lock().map_err(|_| "锁状态被占用")?;
+    if windows.is_empty() {
+        return Ok(());
+    }
+    append_app_log(app, &format!("锁屏关闭开始 labels={}", windows.len()));
+    for entry in windows.iter() {
+        if let Some(window) = app.get_webview_window(&entry.label) {
             let _ = window.close();
         }
     }
-    labels.clear();
-    append_app_log(&app, &format!("锁屏关闭完成 elapsed_ms={}", start.elapsed().as_millis()));
+    windows.clear();
+    if let Ok(mut session) = state.session.lock() {
+        *session = None;
+    }
+    app.state::<TransitionState>().set_suspended(false);
+    let _ = transition::apply_filter(app, true);
+    append_app_log(app, &format!("锁屏关闭完成 elapsed_ms={}", start.elapsed().as_millis()));
     Ok(())
 }
 
 #[tauri::command]
-fn broadcast_lock_update(app: tauri::AppHandle, payload: LockUpdate) -> Result<(), String> {
-    if let Some(state) = app.try_state::<LockState>() {
-        if let Ok(mut last) = state.last_update.lock() {
-            *last = Some(payload.clone());
-        }
-    }
-    for (_label, window) in app.webview_windows() {
-        let _ = window.emit("lockscreen-update", payload.clone());
+fn get_rest_state(rest: tauri::State<'_, RestState>) -> Result<RestSnapshot, String> {
+    rest.snapshot()
+}
+
+#[tauri::command]
+fn start_rest(app: AppHandle) -> Result<RestSnapshot, String> {
+    rest::act(&app, |rest| rest.start_rest())
+}
+
+#[tauri::command]
+fn end_rest(app: AppHandle) -> Result<RestSnapshot, String> {
+    refuse_strict_exit(&app, "结束休息")?;
+    rest::act(&app, |rest| rest.end_rest())
+}
+
+/// 严格模式的提前结束：带口令时核对口令，否则消耗一次当日次数。
+/// 只在严格锁屏期间有效，其他时候调用不能白白用掉次数
+#[tauri::command]
+fn emergency_exit(app: AppHandle, passphrase: Option<String>) -> Result<RestSnapshot, String> {
+    if !strict_lock_active(&app) {
+        return Err("当前不在严格模式的休息中".into());
     }
-    Ok(())
+    let settings = app.state::<SettingsState>().get();
+    let exit = app
+        .state::<StrictState>()
+        .authorize(&settings, passphrase.as_deref())
+        .inspect_err(|err| append_app_log(&app, &format!("提前结束被拒绝: {}", err)))?;
+    rest::exit_early(&app, exit)
 }
 
 #[tauri::command]
-fn get_lock_update(state: tauri::State<'_, LockState>) -> Option<LockUpdate> {
-    state
-        .last_update
-        .lock()
-        .ok()
-        .and_then(|value| value.clone())
+fn get_strict_status(app: AppHandle) -> StrictStatus {
+    app.state::<StrictState>()
+        .status(&app.state::<SettingsState>().get())
+}
+
+/// 设置或清除应急口令，只保存摘要；严格锁屏期间不能修改
+#[tauri::command]
+fn set_strict_passphrase(app: AppHandle, passphrase: Option<String>) -> Result<StrictStatus, String> {
+    refuse_strict_exit(&app, "修改应急口令")?;
+    let hash = passphrase
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(strict::hash_passphrase);
+    let cleared = hash.is_none();
+    record_settings(&app, |settings| settings.rest.strict.passphrase_hash = hash)?;
+    append_app_log(&app, if cleared { "应急口令已清除" } else { "应急口令已更新" });
+    Ok(get_strict_status(app))
 }
 
 #[tauri::command]
-fn lockscreen_action(app: tauri::AppHandle, action: String) -> Result<(), String> {
-    append_app_log(&app, &format!("锁屏动作: {}", action));
-    for (_label, window) in app.webview_windows() {
-        let _ = window.emit("lockscreen-action", action.clone());
+fn toggle_rest_pause(app: AppHandle) -> Result<RestSnapshot, String> {
+    rest::act(&app, |rest| rest.toggle_pause())
+}
+
+#[tauri::command]
+fn snooze_rest(app: AppHandle) -> Result<RestSnapshot, String> {
+    rest::snooze(&app)
+}
+
+#[tauri::command]
+fn skip_rest(app: AppHandle) -> Result<RestSnapshot, String> {
+    rest::skip(&app)
+}
+
+/// 锁屏窗口刚打开时主动读取一次，之后靠 lockscreen-update 推送
+#[tauri::command]
+fn get_lock_update(app: AppHandle) -> Result<Option<LockUpdate>, String> {
+    let snapshot = app.state::<RestState>().snapshot()?;
+    Ok(lock_update(&app, &snapshot))
+}
+
+fn lock_update(app: &AppHandle, snapshot: &RestSnapshot) -> Option<LockUpdate> {
+    let session = app.state::<LockState>().session.lock().ok()?.clone()?;
+    LockUpdate::new(snapshot, &session, rest::now_ms())
+}
+
+/// 休息调度每个周期和状态变化时调用，只发给锁屏窗口
+fn push_lock_update(app: &AppHandle, snapshot: &RestSnapshot) {
+    let Some(update) = lock_update(app, snapshot) else {
+        return;
+    };
+    let Ok(windows) = app.state::<LockState>().windows.lock().map(|list| list.clone()) else {
+        return;
+    };
+    for window in windows {
+        let _ = app.emit_to(window.label.as_str(), "lockscreen-update", &update);
+    }
+}
+
+/// 往后翻到头时取一张新壁纸，所有屏幕一起换
+fn change_wallpaper(app: &AppHandle, forward: bool) -> Result<(), String> {
+    {
+        let state = app.state::<LockState>();
+        let mut session = state.session.lock().map_err(|_| "锁状态被占用")?;
+        let Some(session) = session.as_mut() else {
+            return Ok(());
+        };
+        if !forward {
+            session.prev();
+        } else if !session.next() {
+            if let Some(path) = pick_lock_wallpaper(app)? {
+                session.push(path);
+            }
+        }
+    }
+    push_lock_update(app, &app.state::<RestState>().snapshot()?);
+    Ok(())
+}
+
+/// 锁屏动作在这里校验并分发：休息相关交给调度，换壁纸改锁屏会话后推给锁屏窗口
+#[tauri::command]
+fn lockscreen_action(
+    app: tauri::AppHandle,
+    action: serde_json::Value,
+) -> Result<(), LockActionError> {
+    let action = LockAction::parse(action)
+        .inspect_err(|err| append_app_log(&app, &format!("锁屏动作被拒绝: {}", err)))?;
+    append_app_log(&app, &format!("锁屏动作: {:?}", action));
+    match action {
+        LockAction::Exit => {
+            refuse_strict_exit(&app, "锁屏退出").map_err(LockActionError::Refused)?;
+            rest::act(&app, |rest| rest.end_rest())?;
+        }
+        LockAction::TogglePause => {
+            rest::act(&app, |rest| rest.toggle_pause())?;
+        }
+        LockAction::Snooze(secs) => {
+            refuse_strict_exit(&app, "锁屏稍后").map_err(LockActionError::Refused)?;
+            let mut snoozed = false;
+            rest::act(&app, |rest| {
+                snoozed = rest.snooze_rest(Duration::from_secs(secs));
+                snoozed
+            })?;
+            if !snoozed {
+                return Err(LockActionError::Refused("本次休息不能再推迟".into()));
+            }
+        }
+        LockAction::NextWallpaper => change_wallpaper(&app, true)?,
+        LockAction::PrevWallpaper => change_wallpaper(&app, false)?,
     }
     Ok(())
 }
@@ -300,7 +1143,7 @@ fn load_wallpaper_state(path: &Path) -> WallpaperState {
 
 fn save_wallpaper_state(path: &Path, state: &WallpaperState) -> Result<(), String> {
     let data = serde_json::to_string_pretty(state).map_err(|err| err.to_string())?;
-    fs::write(path, data).map_err(|err| err.to_string())
+    settings::write_atomic(path, data.as_bytes())
 }
 
 fn ensure_wallpaper_dir(app: &AppHandle) -> Result<PathBuf, String> {
@@ -312,6 +1155,15 @@ fn ensure_wallpaper_dir(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(dir)
 }
 
+fn ensure_config_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|err| err.to_string())?;
+    fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+    Ok(dir)
+}
+
 fn append_app_log(app: &AppHandle, message: &str) {
     let dir = match ensure_wallpaper_dir(app) {
         Ok(dir) => dir,
@@ -670,12 +1522,17 @@ fn prefetch_lock_wallpaper(app: AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
-fn get_lock_wallpaper(app: AppHandle, state: tauri::State<'_, AppState>) -> Result<Option<String>, String> {
+fn get_lock_wallpaper(app: AppHandle) -> Result<Option<String>, String> {
+    pick_lock_wallpaper(&app)
+}
+
+fn pick_lock_wallpaper(app: &AppHandle) -> Result<Option<String>, String> {
+    let state = app.state::<AppState>();
     let _guard = state
         .wallpaper_lock
         .lock()
         .map_err(|_| "壁纸锁被占用")?;
-    let dir = ensure_wallpaper_dir(&app)?;
+    let dir = ensure_wallpaper_dir(app)?;
     let state_path = dir.join("index.json");
     let mut wall_state = load_wallpaper_state(&state_path);
     prune_missing_files(&mut wall_state);
@@ -683,7 +1540,7 @@ fn get_lock_wallpaper(app: AppHandle, state: tauri::State<'_, AppState>) -> Resu
 
     if wall_state.files.is_empty() {
         save_wallpaper_state(&state_path, &wall_state)?;
-        append_wallpaper_log(&app, "锁屏读取: 无缓存壁纸");
+        append_wallpaper_log(app, "锁屏读取: 无缓存壁纸");
         return Ok(None);
     }
 
@@ -712,15 +1569,16 @@ fn get_lock_wallpaper(app: AppHandle, state: tauri::State<'_, AppState>) -> Resu
     let chosen = wall_state.files[show_index].path.clone();
     wall_state.files[show_index].last_shown_at = now_ts();
     save_wallpaper_state(&state_path, &wall_state)?;
-    append_wallpaper_log(&app, &format!("锁屏读取: {}", chosen));
+    append_wallpaper_log(app, &format!("锁屏读取: {}", chosen));
     Ok(Some(chosen))
 }
 
 
 #[tauri::command]
 fn request_quit(app: AppHandle, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    refuse_strict_exit(&app, "退出应用")?;
     state.allow_exit.store(true, Ordering::SeqCst);
-    let _ = apply_gamma(1.0, 1.0, 1.0);
+    restore_gamma(&app);
     let _ = app.exit(0);
     Ok(())
 }
@@ -732,17 +1590,42 @@ pub fn run() {
         .setup(|app| {
             // 启动时强制写入壁纸日志，确认目录
             append_wallpaper_log(app.handle(), "应用启动，日志初始化");
+            let backend = app.state::<GammaState>().backend_name();
+            append_app_log(app.handle(), &format!("gamma 后端: {}", backend));
+            if let Ok(dir) = ensure_config_dir(app.handle()) {
+                recover_gamma(app.handle(), dir.join(gamma::recovery::MARKER_FILE));
+                app.state::<FilterState>()
+                    .load_overrides(&dir.join("gamma_outputs.json"));
+                app.state::<SolarState>()
+                    .load(&dir.join("solar_schedule.json"));
+                app.state::<CurveState>()
+                    .load(&dir.join("color_curve.json"));
+                app.state::<PresetState>()
+                    .load(&dir.join("presets.json"));
+                app.state::<StrictState>()
+                    .load(dir.join(strict::LEDGER_FILE));
+                let settings = app.state::<SettingsState>();
+                if let Err(err) = settings.load(dir.join(settings::SETTINGS_FILE)) {
+                    append_app_log(app.handle(), &format!("设置读取失败，使用默认值: {}", err));
+                }
+                if let Err(err) = load_settings_into_state(app.handle(), &settings.get()) {
+                    append_app_log(app.handle(), &format!("设置应用失败: {}", err));
+                }
+            }
+            let handle = app.handle().clone();
+            std::thread::spawn(move || watch_gamma(handle));
+            let handle = app.handle().clone();
+            std::thread::spawn(move || solar::run_solar_schedule(handle));
+            let handle = app.handle().clone();
+            std::thread::spawn(move || curve::run_curve_schedule(handle));
+            let handle = app.handle().clone();
+            std::thread::spawn(move || rest::run_rest_scheduler(handle));
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.center();
                 let _ = window.show();
                 let _ = window.set_focus();
             }
-            let tray_menu = MenuBuilder::new(app)
-                .text("tray_show", "显示主界面")
-                .text("tray_hide", "隐藏到托盘")
-                .separator()
-                .text("tray_quit", "退出")
-                .build()?;
+            let tray_menu = build_tray_menu(app.handle())?;
 
             let tray = TrayIconBuilder::new()
                 .icon(TRAY_ICON.clone())
@@ -782,6 +1665,12 @@ pub fn run() {
                     }
                 })
                 .on_menu_event(|app, event| {
+                    if let Some(name) = event.id().as_ref().strip_prefix("preset:") {
+                        if let Err(err) = apply_preset_by_name(app, name) {
+                            append_app_log(app, &format!("托盘应用预设失败: {}", err));
+                        }
+                        return;
+                    }
                     let Some(window) = app.get_webview_window("main") else {
                         return;
                     };
@@ -794,10 +1683,13 @@ pub fn run() {
                             let _ = window.hide();
                         }
                         "tray_quit" => {
+                            if refuse_strict_exit(app, "退出应用").is_err() {
+                                return;
+                            }
                             if let Some(state) = app.try_state::<AppState>() {
                                 state.allow_exit.store(true, Ordering::SeqCst);
                             }
-                            let _ = apply_gamma(1.0, 1.0, 1.0);
+                            restore_gamma(app);
                             app.exit(0);
                         }
                         _ => {}
@@ -809,6 +1701,29 @@ pub fn run() {
             Ok(())
         })
         .on_window_event(|window, event| {
+            if window.label().starts_with("lockscreen-") {
+                match event {
+                    WindowEvent::CloseRequested { api, .. }
+                        if strict_lock_active(window.app_handle()) =>
+                    {
+                        api.prevent_close();
+                    }
+                    WindowEvent::Focused(false) if strict_lock_active(window.app_handle()) => {
+                        reclaim_lock_focus(window.app_handle(), window.label().to_string());
+                    }
+                    // 缩放变化时立即重新摆放，不等调度轮询；建窗口不能在事件回调里同步进行
+                    WindowEvent::ScaleFactorChanged { .. } => {
+                        let app = window.app_handle().clone();
+                        std::thread::spawn(move || {
+                            if let Err(err) = rest::sync(&app) {
+                                append_app_log(&app, &format!("锁屏恢复失败: {}", err));
+                            }
+                        });
+                    }
+                    _ => {}
+                }
+                return;
+            }
             if window.label() != "main" {
                 return;
             }
@@ -821,23 +1736,66 @@ pub fn run() {
                             return;
                         }
                     }
-                    let _ = apply_gamma(1.0, 1.0, 1.0);
+                    restore_gamma(window.app_handle());
                 }
                 WindowEvent::Destroyed => {
-                    let _ = apply_gamma(1.0, 1.0, 1.0);
+                    restore_gamma(window.app_handle());
                 }
                 _ => {}
             }
         })
         .manage(LockState::default())
         .manage(AppState::default())
+        .manage(GammaState::default())
+        .manage(FilterState::default())
+        .manage(TransitionState::default())
+        .manage(SolarState::default())
+        .manage(CurveState::default())
+        .manage(PresetState::default())
+        .manage(SettingsState::default())
+        .manage(RestState::default())
+        .manage(StrictState::default())
         .invoke_handler(tauri::generate_handler![
             greet,
             set_gamma,
             reset_gamma,
+            list_display_outputs,
+            set_output_gamma,
+            clear_output_gamma,
+            set_tone_curve,
+            get_solar_schedule,
+            set_solar_location,
+            set_solar_schedule,
+            get_color_curve,
+            set_color_curve_enabled,
+            add_curve_keyframe,
+            update_curve_keyframe,
+            delete_curve_keyframe,
+            list_presets,
+            get_active_preset,
+            create_preset,
+            update_preset,
+            delete_preset,
+            apply_preset,
+            set_color_algorithm,
+            set_transition_duration,
+            get_settings,
+            update_settings,
+            export_settings,
+            import_settings,
+            reset_settings,
             show_lock_windows,
             hide_lock_windows,
-            broadcast_lock_update,
+            get_rest_state,
+            start_rest,
+            end_rest,
+            toggle_rest_pause,
+            snooze_rest,
+            skip_rest,
+            end_micro_break,
+            emergency_exit,
+            get_strict_status,
+            set_strict_passphrase,
             get_lock_update,
             lockscreen_action,
             get_lock_wallpaper,
@@ -848,3 +1806,4 @@ pub fn run() {
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
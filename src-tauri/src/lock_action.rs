@@ -0,0 +1,113 @@
+// 锁屏窗口发给后端的动作：在 Rust 侧校验，未知动作直接拒绝，不再原样转发
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// 稍后的最长时长
+const MAX_SNOOZE_SECS: u64 = 30 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LockAction {
+    Exit,
+    TogglePause,
+    NextWallpaper,
+    PrevWallpaper,
+    /// 先离开锁屏，若干秒后再回来休息
+    Snooze(u64),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum LockActionError {
+    Unknown(String),
+    Invalid(String),
+    /// 严格模式或推迟次数用完等情况下不允许
+    Refused(String),
+    Failed(String),
+}
+
+impl fmt::Display for LockActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(message)
+            | Self::Invalid(message)
+            | Self::Refused(message)
+            | Self::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for LockActionError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
+impl LockAction {
+    pub fn parse(value: Value) -> Result<Self, LockActionError> {
+        let action: Self = serde_json::from_value(value.clone())
+            .map_err(|_| LockActionError::Unknown(format!("未知的锁屏动作: {}", value)))?;
+        if let Self::Snooze(secs) = action {
+            if !(1..=MAX_SNOOZE_SECS).contains(&secs) {
+                return Err(LockActionError::Invalid(format!(
+                    "稍后时长需在 1 到 {} 秒之间",
+                    MAX_SNOOZE_SECS
+                )));
+            }
+        }
+        Ok(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_known_actions() {
+        assert_eq!(LockAction::parse(json!("exit")), Ok(LockAction::Exit));
+        assert_eq!(
+            LockAction::parse(json!("togglePause")),
+            Ok(LockAction::TogglePause)
+        );
+        assert_eq!(
+            LockAction::parse(json!("nextWallpaper")),
+            Ok(LockAction::NextWallpaper)
+        );
+        assert_eq!(
+            LockAction::parse(json!({ "snooze": 300 })),
+            Ok(LockAction::Snooze(300))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_and_out_of_range() {
+        assert!(matches!(
+            LockAction::parse(json!("toggle_pause")),
+            Err(LockActionError::Unknown(_))
+        ));
+        assert!(matches!(
+            LockAction::parse(json!({ "snooze": "300" })),
+            Err(LockActionError::Unknown(_))
+        ));
+        assert!(matches!(
+            LockAction::parse(json!({ "snooze": 0 })),
+            Err(LockActionError::Invalid(_))
+        ));
+        assert!(matches!(
+            LockAction::parse(json!({ "snooze": 3600 })),
+            Err(LockActionError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn error_serializes_with_kind() {
+        let error = LockActionError::Refused("不允许".into());
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({ "kind": "refused", "message": "不允许" })
+        );
+    }
+}
@@ -0,0 +1,221 @@
+// 锁屏窗口的摆放：每块显示器对应的窗口矩形，以及热插拔、分辨率或缩放变化后，
+// 哪些显示器要新建窗口、哪些窗口要关闭、哪些要重新摆放
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    /// 物理像素
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale: f64,
+}
+
+impl MonitorInfo {
+    pub fn from_monitor(monitor: &tauri::Monitor) -> Self {
+        Self {
+            name: monitor.name().cloned(),
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+            scale: monitor.scale_factor(),
+        }
+    }
+
+    /// 有名称时按名称认定同一块屏幕，否则只能按原点
+    fn same_display(&self, other: &Self) -> bool {
+        match (&self.name, &other.name) {
+            (Some(left), Some(right)) => left == right,
+            _ => self.x == other.x && self.y == other.y,
+        }
+    }
+}
+
+/// 物理像素下的窗口矩形
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 锁屏窗口比显示器四周各大出 overscan 个逻辑像素。全程按物理像素算，
+/// 缩放不同的屏幕拼在一起、或位于主屏左侧上方的负坐标屏幕都不会错位
+pub fn window_rect(monitor: &MonitorInfo, overscan: u32) -> WindowRect {
+    let pad = (overscan as f64 * monitor.scale).ceil() as u32;
+    WindowRect {
+        x: monitor.x - pad as i32,
+        y: monitor.y - pad as i32,
+        width: monitor.width + pad * 2,
+        height: monitor.height + pad * 2,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockWindow {
+    pub label: String,
+    pub monitor: MonitorInfo,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Reconcile {
+    pub create: Vec<MonitorInfo>,
+    pub close: Vec<String>,
+    /// 显示器还在，但位置、分辨率或缩放变了
+    pub reposition: Vec<(String, MonitorInfo)>,
+}
+
+impl Reconcile {
+    pub fn is_empty(&self) -> bool {
+        self.create.is_empty() && self.close.is_empty() && self.reposition.is_empty()
+    }
+}
+
+pub fn reconcile(windows: &[LockWindow], monitors: &[MonitorInfo]) -> Reconcile {
+    let mut plan = Reconcile::default();
+    let mut claimed = vec![false; monitors.len()];
+    for window in windows {
+        let found = monitors
+            .iter()
+            .enumerate()
+            .find(|(index, monitor)| !claimed[*index] && window.monitor.same_display(monitor));
+        match found {
+            Some((index, monitor)) => {
+                claimed[index] = true;
+                if *monitor != window.monitor {
+                    plan.reposition
+                        .push((window.label.clone(), monitor.clone()));
+                }
+            }
+            None => plan.close.push(window.label.clone()),
+        }
+    }
+    plan.create = monitors
+        .iter()
+        .zip(claimed)
+        .filter(|(_, claimed)| !claimed)
+        .map(|(monitor, _)| monitor.clone())
+        .collect();
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: i32, scale: f64) -> MonitorInfo {
+        MonitorInfo {
+            name: Some(name.to_string()),
+            x,
+            y: 0,
+            width: 1920,
+            height: 1080,
+            scale,
+        }
+    }
+
+    fn window(label: &str, monitor: MonitorInfo) -> LockWindow {
+        LockWindow {
+            label: label.to_string(),
+            monitor,
+        }
+    }
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> WindowRect {
+        WindowRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn overscan_scales_with_each_monitor() {
+        // 1x 主屏右侧接一块 2x 的 4K 屏
+        let primary = monitor("DP-1", 0, 1.0);
+        let hidpi = MonitorInfo {
+            width: 3840,
+            height: 2160,
+            ..monitor("DP-2", 1920, 2.0)
+        };
+        assert_eq!(window_rect(&primary, 200), rect(-200, -200, 2320, 1480));
+        assert_eq!(window_rect(&hidpi, 200), rect(1520, -400, 4640, 2960));
+        assert_eq!(window_rect(&hidpi, 0), rect(1920, 0, 3840, 2160));
+    }
+
+    #[test]
+    fn negative_origins_stay_on_their_monitor() {
+        // 主屏左侧 1.25x 的 2560 宽屏，原点为负
+        let left = MonitorInfo {
+            width: 2560,
+            height: 1440,
+            ..monitor("HDMI-1", -2560, 1.25)
+        };
+        let r = window_rect(&left, 200);
+        assert_eq!(r, rect(-2810, -250, 3060, 1940));
+        // 右边缘刚好越过主屏原点一圈外扩
+        assert_eq!(r.x + r.width as i32, 250);
+    }
+
+    #[test]
+    fn stacked_monitors_use_vertical_origin() {
+        let above = MonitorInfo {
+            y: -1440,
+            width: 2560,
+            height: 1440,
+            ..monitor("DP-3", -320, 1.5)
+        };
+        let below = monitor("DP-1", 0, 1.0);
+        assert_eq!(window_rect(&above, 100), rect(-470, -1590, 2860, 1740));
+        assert_eq!(window_rect(&below, 100), rect(-100, -100, 2120, 1280));
+    }
+
+    #[test]
+    fn unchanged_layout_needs_nothing() {
+        let monitors = vec![monitor("DP-1", 0, 1.0), monitor("HDMI-1", 1920, 1.0)];
+        let windows = vec![
+            window("lockscreen-0", monitors[0].clone()),
+            window("lockscreen-1", monitors[1].clone()),
+        ];
+        assert!(reconcile(&windows, &monitors).is_empty());
+    }
+
+    #[test]
+    fn plugged_and_unplugged_monitors() {
+        let windows = vec![
+            window("lockscreen-0", monitor("DP-1", 0, 1.0)),
+            window("lockscreen-1", monitor("HDMI-1", 1920, 1.0)),
+        ];
+        let monitors = vec![monitor("DP-1", 0, 1.0), monitor("DP-2", -2560, 1.0)];
+        let plan = reconcile(&windows, &monitors);
+        assert_eq!(plan.close, vec!["lockscreen-1".to_string()]);
+        assert_eq!(plan.create, vec![monitor("DP-2", -2560, 1.0)]);
+        assert!(plan.reposition.is_empty());
+    }
+
+    #[test]
+    fn scale_or_position_change_repositions() {
+        let windows = vec![window("lockscreen-0", monitor("DP-1", 0, 1.0))];
+        let plan = reconcile(&windows, &[monitor("DP-1", 0, 1.5)]);
+        assert_eq!(
+            plan.reposition,
+            vec![("lockscreen-0".to_string(), monitor("DP-1", 0, 1.5))]
+        );
+        assert!(plan.create.is_empty() && plan.close.is_empty());
+    }
+
+    #[test]
+    fn unnamed_monitors_match_by_origin() {
+        let unnamed = |x| MonitorInfo {
+            name: None,
+            ..monitor("", x, 1.0)
+        };
+        let windows = vec![window("lockscreen-0", unnamed(0))];
+        let plan = reconcile(&windows, &[unnamed(1920)]);
+        assert_eq!(plan.close, vec!["lockscreen-0".to_string()]);
+        assert_eq!(plan.create, vec![unnamed(1920)]);
+    }
+}
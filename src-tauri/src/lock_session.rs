@@ -0,0 +1,127 @@
+// 锁屏会话：倒计时、暂停和壁纸都以后端为准，由休息调度定时推给各锁屏窗口，
+// 窗口只负责按本地时区格式化，主窗口被节流也不会让锁屏停住
+use crate::rest::RestSnapshot;
+use serde::Serialize;
+
+/// 本次锁屏看过的壁纸，所有屏幕共用
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LockSession {
+    wallpapers: Vec<String>,
+    index: usize,
+}
+
+impl LockSession {
+    pub fn new(wallpaper: Option<String>) -> Self {
+        Self {
+            wallpapers: wallpaper.into_iter().collect(),
+            index: 0,
+        }
+    }
+
+    pub fn wallpaper(&self) -> Option<&str> {
+        self.wallpapers.get(self.index).map(String::as_str)
+    }
+
+    /// 往回翻看过的壁纸，已在第一张时不动
+    pub fn prev(&mut self) -> bool {
+        if self.index == 0 {
+            return false;
+        }
+        self.index -= 1;
+        true
+    }
+
+    /// 往后翻看过的壁纸；已在最后一张时返回 false，由调用方取一张新的
+    pub fn next(&mut self) -> bool {
+        if self.index + 1 >= self.wallpapers.len() {
+            return false;
+        }
+        self.index += 1;
+        true
+    }
+
+    pub fn push(&mut self, wallpaper: String) {
+        self.wallpapers.push(wallpaper);
+        self.index = self.wallpapers.len() - 1;
+    }
+}
+
+/// 推给锁屏窗口的数值状态，时间均为毫秒
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockUpdate {
+    pub now_ms: i64,
+    /// 计时中时的结束时间戳，暂停时为 None
+    pub end_at_ms: Option<i64>,
+    pub remaining_ms: u64,
+    pub duration_ms: u64,
+    pub paused: bool,
+    pub allow_esc_exit: bool,
+    pub strict: bool,
+    pub snoozes_left: u32,
+    pub snooze_ms: u64,
+    /// 壁纸文件路径
+    pub wallpaper: Option<String>,
+}
+
+impl LockUpdate {
+    /// 不在休息时返回 None
+    pub fn new(snapshot: &RestSnapshot, session: &LockSession, now_ms: i64) -> Option<Self> {
+        if !snapshot.is_locked() {
+            return None;
+        }
+        let remaining_ms = snapshot.rest_remaining_ms.unwrap_or(0);
+        let paused = snapshot.phase == "paused";
+        Some(Self {
+            now_ms,
+            end_at_ms: (!paused).then_some(now_ms + remaining_ms as i64),
+            remaining_ms,
+            duration_ms: snapshot.rest_duration_ms,
+            paused,
+            allow_esc_exit: snapshot.allow_esc_exit,
+            strict: snapshot.strict,
+            snoozes_left: snapshot.snoozes_left,
+            snooze_ms: snapshot.snooze_ms,
+            wallpaper: session.wallpaper().map(str::to_string),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::{RestConfig, RestScheduler, SystemClock};
+
+    #[test]
+    fn wallpaper_history_navigation() {
+        let mut session = LockSession::new(Some("a.jpg".into()));
+        assert!(!session.prev());
+        assert!(!session.next());
+        session.push("b.jpg".into());
+        assert_eq!(session.wallpaper(), Some("b.jpg"));
+        assert!(session.prev());
+        assert_eq!(session.wallpaper(), Some("a.jpg"));
+        assert!(session.next());
+        assert_eq!(session.wallpaper(), Some("b.jpg"));
+        assert_eq!(LockSession::new(None).wallpaper(), None);
+    }
+
+    #[test]
+    fn update_only_while_locked() {
+        let mut rest = RestScheduler::new(Box::new(SystemClock), RestConfig::default());
+        let session = LockSession::new(Some("a.jpg".into()));
+        assert_eq!(LockUpdate::new(&rest.snapshot(), &session, 1_000), None);
+
+        rest.start_rest();
+        let update = LockUpdate::new(&rest.snapshot(), &session, 1_000).unwrap();
+        assert!(!update.paused);
+        assert_eq!(update.end_at_ms, Some(1_000 + update.remaining_ms as i64));
+        assert_eq!(update.wallpaper.as_deref(), Some("a.jpg"));
+
+        rest.toggle_pause();
+        let update = LockUpdate::new(&rest.snapshot(), &session, 1_000).unwrap();
+        assert!(update.paused);
+        assert_eq!(update.end_at_ms, None);
+        assert!(update.remaining_ms > 0);
+    }
+}
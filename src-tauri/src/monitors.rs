@@ -0,0 +1,140 @@
+// 界面里的显示器与 gamma 后端输出的对应：Linux 上 tauri 给的是显示器型号，
+// 后端用的是 HDMI-1 之类的接口名，只能按桌面上的位置和大小配对
+use crate::gamma::GammaOutput;
+use crate::lock_layout::MonitorInfo;
+
+fn same_bounds(monitor: &MonitorInfo, output: &GammaOutput) -> bool {
+    output.bounds.is_some_and(|bounds| {
+        bounds.x == monitor.x
+            && bounds.y == monitor.y
+            && bounds.width == monitor.width
+            && bounds.height == monitor.height
+    })
+}
+
+/// 分数缩放下两边算出的尺寸可能差几个像素，原点一般仍一致
+fn same_origin(monitor: &MonitorInfo, output: &GammaOutput) -> bool {
+    output
+        .bounds
+        .is_some_and(|bounds| bounds.x == monitor.x && bounds.y == monitor.y)
+}
+
+/// Windows 上两边都是 `\\.\DISPLAY1` 这样的设备名
+fn same_name(monitor: &MonitorInfo, output: &GammaOutput) -> bool {
+    monitor.name.as_deref() == Some(output.id.as_str())
+}
+
+/// 与 `monitors` 一一对应的后端输出 id，找不到时为 None。
+/// 依次按完整区域、原点、同名配对，最后各剩一个时直接配上
+pub fn match_outputs(monitors: &[MonitorInfo], outputs: &[GammaOutput]) -> Vec<Option<String>> {
+    let mut matched: Vec<Option<usize>> = vec![None; monitors.len()];
+    let mut claimed = vec![false; outputs.len()];
+    let passes: [fn(&MonitorInfo, &GammaOutput) -> bool; 3] = [same_bounds, same_origin, same_name];
+    for pass in passes {
+        for (monitor, slot) in monitors.iter().zip(matched.iter_mut()) {
+            if slot.is_some() {
+                continue;
+            }
+            let found = (0..outputs.len())
+                .find(|index| !claimed[*index] && pass(monitor, &outputs[*index]));
+            if let Some(index) = found {
+                claimed[index] = true;
+                *slot = Some(index);
+            }
+        }
+    }
+    let free_monitors: Vec<usize> = (0..monitors.len())
+        .filter(|index| matched[*index].is_none())
+        .collect();
+    let free_outputs: Vec<usize> = (0..outputs.len())
+        .filter(|index| !claimed[*index])
+        .collect();
+    if let ([monitor], [output]) = (free_monitors.as_slice(), free_outputs.as_slice()) {
+        matched[*monitor] = Some(*output);
+    }
+    matched
+        .into_iter()
+        .map(|index| index.map(|index| outputs[index].id.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gamma::OutputBounds;
+
+    fn monitor(name: &str, x: i32, width: u32) -> MonitorInfo {
+        MonitorInfo {
+            name: Some(name.to_string()),
+            x,
+            y: 0,
+            width,
+            height: 1080,
+            scale: 1.0,
+        }
+    }
+
+    fn output(id: &str, x: i32, width: u32) -> GammaOutput {
+        GammaOutput {
+            id: id.to_string(),
+            ramp_size: 256,
+            bounds: Some(OutputBounds {
+                x,
+                y: 0,
+                width,
+                height: 1080,
+            }),
+        }
+    }
+
+    #[test]
+    fn gtk_model_names_match_connectors_by_geometry() {
+        // GDK 报的是型号，RandR 报的是接口名
+        let monitors = vec![
+            monitor("DELL U2720Q", 1920, 3840),
+            monitor("LG HDR", 0, 1920),
+        ];
+        let outputs = vec![output("HDMI-1", 0, 1920), output("DP-1", 1920, 3840)];
+        assert_eq!(
+            match_outputs(&monitors, &outputs),
+            vec![Some("DP-1".to_string()), Some("HDMI-1".to_string())]
+        );
+    }
+
+    #[test]
+    fn falls_back_to_origin_name_and_last_pair() {
+        let monitors = vec![
+            // 缩放导致尺寸对不上，原点一致
+            monitor("A", 0, 2560),
+            // Windows 上两边名称一致
+            monitor("\\\\.\\DISPLAY2", 5000, 1920),
+            monitor("B", 9000, 1920),
+        ];
+        let outputs = vec![
+            output("eDP-1", 0, 1280),
+            GammaOutput {
+                bounds: None,
+                ..output("\\\\.\\DISPLAY2", 0, 0)
+            },
+            output("DP-3", 7000, 1920),
+        ];
+        assert_eq!(
+            match_outputs(&monitors, &outputs),
+            vec![
+                Some("eDP-1".to_string()),
+                Some("\\\\.\\DISPLAY2".to_string()),
+                Some("DP-3".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unmatched_monitor_has_no_output() {
+        let monitors = vec![monitor("A", 0, 1920), monitor("B", 1920, 1920)];
+        let outputs = vec![output("HDMI-1", 0, 1920)];
+        assert_eq!(
+            match_outputs(&monitors, &outputs),
+            vec![Some("HDMI-1".to_string()), None]
+        );
+    }
+}
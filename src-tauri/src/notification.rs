@@ -0,0 +1,92 @@
+// 休息预告的系统通知：Linux 走 freedesktop 通知服务，Windows 走 WinRT toast，
+// 通知上的「稍后」「跳过」按钮直接回到休息调度
+use std::time::Duration;
+use tauri::AppHandle;
+
+const ACTION_SNOOZE: &str = "snooze";
+const ACTION_SKIP: &str = "skip";
+
+fn handle_action(app: &AppHandle, action: &str) {
+    let result = match action {
+        ACTION_SNOOZE => crate::rest::snooze(app),
+        ACTION_SKIP => crate::rest::skip(app),
+        _ => return,
+    };
+    if let Err(err) = result {
+        crate::append_app_log(app, &format!("通知操作失败: {}", err));
+    }
+}
+
+fn body(seconds: u64) -> String {
+    format!("{} 秒后进入休息，先保存手头的工作吧", seconds)
+}
+
+fn snooze_label(snooze: Duration) -> String {
+    format!("稍后 {} 分钟", snooze.as_secs().div_ceil(60))
+}
+
+/// 推迟次数用完时 snooze 为 None，只保留跳过按钮
+#[cfg(target_os = "linux")]
+pub fn show_break_warning(
+    app: &AppHandle,
+    seconds: u64,
+    snooze: Option<Duration>,
+) -> Result<(), String> {
+    let mut notification = notify_rust::Notification::new();
+    notification
+        .appname("护眼吧")
+        .summary("即将休息")
+        .body(&body(seconds))
+        .timeout(Duration::from_secs(seconds));
+    if let Some(snooze) = snooze {
+        notification.action(ACTION_SNOOZE, &snooze_label(snooze));
+    }
+    notification.action(ACTION_SKIP, "跳过本次休息");
+    let handle = notification.show().map_err(|err| err.to_string())?;
+    let app = app.clone();
+    // 等待按钮回调会阻塞到通知关闭，放到单独线程
+    std::thread::spawn(move || handle.wait_for_action(|action| handle_action(&app, action)));
+    Ok(())
+}
+
+#[cfg(windows)]
+pub fn show_break_warning(
+    app: &AppHandle,
+    seconds: u64,
+    snooze: Option<Duration>,
+) -> Result<(), String> {
+    use tauri_winrt_notification::{Duration as ToastDuration, Toast};
+    // 开发模式下应用没有注册 AppUserModelID，借用 PowerShell 的才能弹出
+    let app_id = if cfg!(debug_assertions) {
+        Toast::POWERSHELL_APP_ID.to_string()
+    } else {
+        app.config().identifier.clone()
+    };
+    let mut toast = Toast::new(&app_id)
+        .title("即将休息")
+        .text1(&body(seconds))
+        .duration(ToastDuration::Long);
+    if let Some(snooze) = snooze {
+        toast = toast.add_button(&snooze_label(snooze), ACTION_SNOOZE);
+    }
+    let app = app.clone();
+    toast
+        .add_button("跳过本次休息", ACTION_SKIP)
+        .on_activated(move |action| {
+            if let Some(action) = action {
+                handle_action(&app, &action);
+            }
+            Ok(())
+        })
+        .show()
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn show_break_warning(
+    _app: &AppHandle,
+    _seconds: u64,
+    _snooze: Option<Duration>,
+) -> Result<(), String> {
+    Ok(())
+}
@@ -0,0 +1,280 @@
+// 预设目录：内置预设 + 用户自定义预设（保存在 presets.json），托盘与界面共用
+use crate::solar::SolarPhase;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+const MAX_NAME_CHARS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestCadence {
+    pub work_minutes: u32,
+    pub rest_minutes: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Preset {
+    pub name: String,
+    pub day: SolarPhase,
+    pub night: SolarPhase,
+    /// 为 true 时按日出日落在白天/夜间之间渐变，否则只用白天设置
+    #[serde(default)]
+    pub follow_sun: bool,
+    #[serde(default)]
+    pub rest: Option<RestCadence>,
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+fn builtin(name: &str, day: (f64, f64), night: (f64, f64), follow_sun: bool) -> Preset {
+    Preset {
+        name: name.to_string(),
+        day: SolarPhase {
+            color_temp: day.0,
+            strength: day.1,
+        },
+        night: SolarPhase {
+            color_temp: night.0,
+            strength: night.1,
+        },
+        follow_sun,
+        rest: None,
+        builtin: true,
+    }
+}
+
+pub fn builtin_presets() -> Vec<Preset> {
+    vec![
+        builtin("智能", (4700.0, 30.0), (3400.0, 30.0), true),
+        builtin("办公", (5200.0, 50.0), (4700.0, 60.0), false),
+        builtin("影视", (5600.0, 45.0), (5200.0, 55.0), false),
+        builtin("游戏", (6000.0, 35.0), (5600.0, 45.0), false),
+    ]
+}
+
+impl Preset {
+    pub fn validate(&self) -> Result<(), String> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err("预设名称不能为空".into());
+        }
+        if name.chars().count() > MAX_NAME_CHARS {
+            return Err(format!("预设名称不能超过 {} 个字", MAX_NAME_CHARS));
+        }
+        self.day.validate()?;
+        self.night.validate()?;
+        if let Some(rest) = self.rest {
+            if !(1..=240).contains(&rest.work_minutes) {
+                return Err("工作时长需在 1 到 240 分钟之间".into());
+            }
+            if !(1..=60).contains(&rest.rest_minutes) {
+                return Err("休息时长需在 1 到 60 分钟之间".into());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct PresetState {
+    custom: Mutex<Vec<Preset>>,
+    active: Mutex<Option<String>>,
+}
+
+impl PresetState {
+    pub fn custom(&self) -> Vec<Preset> {
+        self.custom
+            .lock()
+            .map(|value| value.clone())
+            .unwrap_or_default()
+    }
+
+    /// 内置在前，自定义在后
+    pub fn list(&self) -> Vec<Preset> {
+        let mut presets = builtin_presets();
+        presets.extend(self.custom());
+        presets
+    }
+
+    pub fn find(&self, name: &str) -> Result<Preset, String> {
+        self.list()
+            .into_iter()
+            .find(|preset| preset.name == name)
+            .ok_or_else(|| format!("预设不存在: {}", name))
+    }
+
+    pub fn active(&self) -> Option<String> {
+        self.active.lock().ok().and_then(|value| value.clone())
+    }
+
+    pub fn set_active(&self, name: Option<String>) {
+        if let Ok(mut active) = self.active.lock() {
+            *active = name;
+        }
+    }
+
+    fn ensure_name_free(&self, name: &str, except: Option<&str>) -> Result<(), String> {
+        let taken = self
+            .list()
+            .iter()
+            .any(|preset| preset.name == name && Some(preset.name.as_str()) != except);
+        if taken {
+            return Err(format!("预设名称已存在: {}", name));
+        }
+        Ok(())
+    }
+
+    pub fn create(&self, mut preset: Preset) -> Result<Preset, String> {
+        preset.validate()?;
+        preset.name = preset.name.trim().to_string();
+        preset.builtin = false;
+        self.ensure_name_free(&preset.name, None)?;
+        let mut custom = self.custom.lock().map_err(|_| "预设被占用")?;
+        custom.push(preset.clone());
+        Ok(preset)
+    }
+
+    pub fn update(&self, name: &str, mut preset: Preset) -> Result<Preset, String> {
+        if builtin_presets().iter().any(|item| item.name == name) {
+            return Err("内置预设不能修改".into());
+        }
+        preset.validate()?;
+        preset.name = preset.name.trim().to_string();
+        preset.builtin = false;
+        self.ensure_name_free(&preset.name, Some(name))?;
+        let mut custom = self.custom.lock().map_err(|_| "预设被占用")?;
+        let slot = custom
+            .iter_mut()
+            .find(|item| item.name == name)
+            .ok_or_else(|| format!("预设不存在: {}", name))?;
+        *slot = preset.clone();
+        drop(custom);
+        if self.active().as_deref() == Some(name) {
+            self.set_active(Some(preset.name.clone()));
+        }
+        Ok(preset)
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), String> {
+        if builtin_presets().iter().any(|item| item.name == name) {
+            return Err("内置预设不能删除".into());
+        }
+        let mut custom = self.custom.lock().map_err(|_| "预设被占用")?;
+        let before = custom.len();
+        custom.retain(|item| item.name != name);
+        if custom.len() == before {
+            return Err(format!("预设不存在: {}", name));
+        }
+        drop(custom);
+        if self.active().as_deref() == Some(name) {
+            self.set_active(None);
+        }
+        Ok(())
+    }
+
+    pub fn replace_custom(&self, presets: Vec<Preset>) {
+        if let Ok(mut custom) = self.custom.lock() {
+            *custom = presets;
+        }
+    }
+
+    /// 只加载合法且不与内置重名的条目
+    pub fn load(&self, path: &Path) {
+        let Ok(data) = fs::read_to_string(path) else {
+            return;
+        };
+        let Ok(saved) = serde_json::from_str::<Vec<Preset>>(&data) else {
+            return;
+        };
+        let builtins = builtin_presets();
+        let mut valid: Vec<Preset> = Vec::new();
+        for mut preset in saved {
+            preset.builtin = false;
+            let clash = builtins
+                .iter()
+                .chain(valid.iter())
+                .any(|item| item.name == preset.name);
+            if preset.validate().is_ok() && !clash {
+                valid.push(preset);
+            }
+        }
+        self.replace_custom(valid);
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let data = serde_json::to_string_pretty(&self.custom()).map_err(|err| err.to_string())?;
+        crate::settings::write_atomic(path, data.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(name: &str) -> Preset {
+        Preset {
+            name: name.to_string(),
+            day: SolarPhase {
+                color_temp: 5000.0,
+                strength: 40.0,
+            },
+            night: SolarPhase {
+                color_temp: 3600.0,
+                strength: 60.0,
+            },
+            follow_sun: false,
+            rest: Some(RestCadence {
+                work_minutes: 45,
+                rest_minutes: 5,
+            }),
+            builtin: true,
+        }
+    }
+
+    #[test]
+    fn builtins_come_first_and_are_read_only() {
+        let state = PresetState::default();
+        let names: Vec<String> = state.list().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["智能", "办公", "影视", "游戏"]);
+        assert!(state.update("办公", custom("办公")).is_err());
+        assert!(state.delete("智能").is_err());
+        assert!(state.create(custom("影视")).is_err());
+    }
+
+    #[test]
+    fn custom_preset_crud() {
+        let state = PresetState::default();
+        let created = state.create(custom(" 夜读 ")).unwrap();
+        assert_eq!(created.name, "夜读");
+        assert!(!created.builtin);
+        assert!(state.create(custom("夜读")).is_err());
+
+        state.set_active(Some("夜读".into()));
+        state.update("夜读", custom("深夜阅读")).unwrap();
+        assert_eq!(state.active().as_deref(), Some("深夜阅读"));
+        assert!(state.find("夜读").is_err());
+
+        state.delete("深夜阅读").unwrap();
+        assert_eq!(state.active(), None);
+        assert_eq!(state.list().len(), 4);
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let state = PresetState::default();
+        let mut preset = custom("");
+        assert!(state.create(preset.clone()).is_err());
+        preset.name = "过暖".into();
+        preset.night.color_temp = 200.0;
+        assert!(state.create(preset.clone()).is_err());
+        preset.night.color_temp = 3000.0;
+        preset.rest = Some(RestCadence {
+            work_minutes: 0,
+            rest_minutes: 5,
+        });
+        assert!(state.create(preset).is_err());
+    }
+}
@@ -0,0 +1,556 @@
+// 配置导入导出：设置、自定义预设、日照调度、时间曲线和按显示器设置打包成一个带版本号的文件，
+// 按扩展名选 JSON 或 TOML，便于多台机器共用
+use crate::curve::{ColorCurve, CurveState};
+use crate::filter::{FilterSetting, FilterState};
+use crate::presets::{builtin_presets, Preset, PresetState};
+use crate::settings::{self, FieldError, Settings, SettingsState};
+use crate::solar::{SolarSchedule, SolarState};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// 版本 2 起包含日照调度、时间曲线和按显示器设置
+pub const BUNDLE_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bundle {
+    pub version: u32,
+    pub settings: Settings,
+    pub presets: Vec<Preset>,
+    /// 以下三项缺省时导入不改动本机现状
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solar: Option<SolarSchedule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curve: Option<ColorCurve>,
+    /// 显示器名称 → 单独设置
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<HashMap<String, FilterSetting>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Toml,
+}
+
+impl Format {
+    /// 只有 .toml 走 TOML，其余一律按 JSON
+    pub fn from_path(path: &Path) -> Self {
+        let is_toml = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+        if is_toml {
+            Self::Toml
+        } else {
+            Self::Json
+        }
+    }
+}
+
+impl Bundle {
+    pub fn new(settings: Settings, presets: Vec<Preset>) -> Self {
+        Self {
+            version: BUNDLE_VERSION,
+            settings,
+            presets,
+            solar: None,
+            curve: None,
+            outputs: None,
+        }
+    }
+
+    pub fn render(&self, format: Format) -> Result<String, String> {
+        match format {
+            Format::Json => serde_json::to_string_pretty(self).map_err(|err| err.to_string()),
+            Format::Toml => toml::to_string_pretty(self).map_err(|err| err.to_string()),
+        }
+    }
+
+    /// 收集所有字段错误后一起返回，方便一次改完整个文件
+    pub fn parse(text: &str, format: Format) -> Result<Bundle, Vec<FieldError>> {
+        let parsed: Result<Value, String> = match format {
+            Format::Json => serde_json::from_str(text).map_err(|err| err.to_string()),
+            Format::Toml => toml::from_str(text).map_err(|err| err.to_string()),
+        };
+        let root =
+            parsed.map_err(|err| vec![FieldError::new("文件", format!("无法解析: {}", err))])?;
+        let Value::Object(mut root) = root else {
+            return Err(vec![FieldError::new("文件", "顶层必须是对象")]);
+        };
+        let mut errors = Vec::new();
+        match root.remove("version").map(|value| value.as_u64()) {
+            Some(Some(version)) if (1..=BUNDLE_VERSION as u64).contains(&version) => {}
+            Some(Some(version)) => errors.push(FieldError::new(
+                "version",
+                format!("不支持的版本 {}", version),
+            )),
+            Some(None) => errors.push(FieldError::new("version", "版本号必须是正整数")),
+            None => errors.push(FieldError::new("version", "缺少版本号")),
+        }
+        let presets = parse_presets(root.remove("presets"), &mut errors);
+        let settings = parse_settings(root.remove("settings"), &presets, &mut errors);
+        let solar = parse_solar(root.remove("solar"), &mut errors);
+        let curve = parse_curve(root.remove("curve"), &mut errors);
+        let outputs = parse_outputs(root.remove("outputs"), &mut errors);
+        for key in root.keys() {
+            errors.push(FieldError::new(key.clone(), "未知字段"));
+        }
+        if errors.is_empty() {
+            Ok(Bundle {
+                solar,
+                curve,
+                outputs,
+                ..Bundle::new(settings, presets)
+            })
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// 逐个顶层字段合并进默认值，类型错误只记到对应字段上
+fn parse_settings(
+    value: Option<Value>,
+    presets: &[Preset],
+    errors: &mut Vec<FieldError>,
+) -> Settings {
+    let Some(value) = value else {
+        return Settings::default();
+    };
+    let fields = match settings::upgrade(value) {
+        Ok(Value::Object(fields)) => fields,
+        Ok(_) => return Settings::default(),
+        Err(err) => {
+            errors.push(FieldError::new("settings", err));
+            return Settings::default();
+        }
+    };
+    let mut merged = serde_json::to_value(Settings::default()).unwrap_or(Value::Null);
+    for (key, field) in fields {
+        let name = format!("settings.{}", key);
+        if merged.get(&key).is_none() {
+            errors.push(FieldError::new(name, "未知字段"));
+            continue;
+        }
+        let mut candidate = merged.clone();
+        settings::merge(&mut candidate[&key], &field);
+        match serde_json::from_value::<Settings>(candidate.clone()) {
+            Ok(_) => merged = candidate,
+            Err(err) => errors.push(FieldError::new(name, format!("类型错误: {}", err))),
+        }
+    }
+    let parsed: Settings = serde_json::from_value(merged).unwrap_or_default();
+    for err in parsed.field_errors() {
+        errors.push(FieldError::new(
+            format!("settings.{}", err.field),
+            err.message,
+        ));
+    }
+    if let Some(name) = &parsed.active_preset {
+        let known = builtin_presets()
+            .iter()
+            .chain(presets)
+            .any(|preset| &preset.name == name);
+        if !known {
+            errors.push(FieldError::new(
+                "settings.activePreset",
+                format!("预设不存在: {}", name),
+            ));
+        }
+    }
+    parsed
+}
+
+fn parse_presets(value: Option<Value>, errors: &mut Vec<FieldError>) -> Vec<Preset> {
+    let Some(value) = value else {
+        return Vec::new();
+    };
+    let Value::Array(items) = value else {
+        errors.push(FieldError::new("presets", "必须是数组"));
+        return Vec::new();
+    };
+    let builtins = builtin_presets();
+    let mut presets: Vec<Preset> = Vec::new();
+    for (index, item) in items.into_iter().enumerate() {
+        let name = format!("presets[{}]", index);
+        let mut preset = match serde_json::from_value::<Preset>(item) {
+            Ok(preset) => preset,
+            Err(err) => {
+                errors.push(FieldError::new(name, format!("类型错误: {}", err)));
+                continue;
+            }
+        };
+        if let Err(err) = preset.validate() {
+            errors.push(FieldError::new(name, err));
+            continue;
+        }
+        preset.name = preset.name.trim().to_string();
+        preset.builtin = false;
+        let clash = builtins
+            .iter()
+            .chain(presets.iter())
+            .any(|item| item.name == preset.name);
+        if clash {
+            errors.push(FieldError::new(
+                format!("{}.name", name),
+                format!("预设名称已存在: {}", preset.name),
+            ));
+            continue;
+        }
+        presets.push(preset);
+    }
+    presets
+}
+
+/// 缺省字段取默认值，和设置一样允许只写要改的部分
+fn merge_default<T: Default + Serialize + serde::de::DeserializeOwned>(
+    value: &Value,
+) -> Result<T, String> {
+    let mut merged = serde_json::to_value(T::default()).map_err(|err| err.to_string())?;
+    settings::merge(&mut merged, value);
+    serde_json::from_value(merged).map_err(|err| format!("类型错误: {}", err))
+}
+
+fn parse_solar(value: Option<Value>, errors: &mut Vec<FieldError>) -> Option<SolarSchedule> {
+    let value = value?;
+    if !value.is_object() {
+        errors.push(FieldError::new("solar", "必须是对象"));
+        return None;
+    }
+    let schedule: SolarSchedule = match merge_default(&value) {
+        Ok(schedule) => schedule,
+        Err(err) => {
+            errors.push(FieldError::new("solar", err));
+            return None;
+        }
+    };
+    if let Err(err) = schedule.validate() {
+        errors.push(FieldError::new("solar", err));
+    }
+    Some(schedule)
+}
+
+fn parse_curve(value: Option<Value>, errors: &mut Vec<FieldError>) -> Option<ColorCurve> {
+    let value = value?;
+    if !value.is_object() {
+        errors.push(FieldError::new("curve", "必须是对象"));
+        return None;
+    }
+    let curve: ColorCurve = match merge_default(&value) {
+        Ok(curve) => curve,
+        Err(err) => {
+            errors.push(FieldError::new("curve", err));
+            return None;
+        }
+    };
+    for (index, err) in curve.keyframe_errors() {
+        errors.push(FieldError::new(format!("curve.keyframes[{}]", index), err));
+    }
+    Some(curve)
+}
+
+fn parse_outputs(
+    value: Option<Value>,
+    errors: &mut Vec<FieldError>,
+) -> Option<HashMap<String, FilterSetting>> {
+    let value = value?;
+    let Value::Object(items) = value else {
+        errors.push(FieldError::new("outputs", "必须是对象"));
+        return None;
+    };
+    let mut outputs = HashMap::new();
+    for (id, item) in items {
+        let name = format!("outputs.{}", id);
+        let setting = match serde_json::from_value::<FilterSetting>(item) {
+            Ok(setting) => setting,
+            Err(err) => {
+                errors.push(FieldError::new(name, format!("类型错误: {}", err)));
+                continue;
+            }
+        };
+        if let Err(err) = setting.validate() {
+            errors.push(FieldError::new(name, err));
+            continue;
+        }
+        outputs.insert(id, setting);
+    }
+    Some(outputs)
+}
+
+/// 配置包涉及的各项状态；界面和命令行都经这里打包和写回，两边不会漏项
+pub struct Stores<'a> {
+    pub settings: &'a SettingsState,
+    pub presets: &'a PresetState,
+    pub solar: &'a SolarState,
+    pub curve: &'a CurveState,
+    pub filter: &'a FilterState,
+}
+
+impl Stores<'_> {
+    pub fn bundle(&self) -> Bundle {
+        Bundle {
+            solar: Some(self.solar.schedule()),
+            curve: Some(self.curve.curve()),
+            outputs: Some(self.filter.overrides()),
+            ..Bundle::new(self.settings.get(), self.presets.custom())
+        }
+    }
+
+    /// 写回配置目录；设置本身交给 `replace`，界面要顺带刷新运行状态，命令行只落盘。
+    /// 文件里没有的日照、曲线和显示器设置保持不变
+    pub fn apply(
+        &self,
+        dir: &Path,
+        bundle: Bundle,
+        replace: impl FnOnce(Settings) -> Result<Settings, String>,
+    ) -> Result<Settings, String> {
+        self.presets.replace_custom(bundle.presets);
+        self.presets.save(&dir.join("presets.json"))?;
+        if let Some(schedule) = bundle.solar {
+            self.solar.set_schedule(schedule)?;
+            self.solar.save(&dir.join("solar_schedule.json"))?;
+        }
+        if let Some(imported) = bundle.curve {
+            self.curve.update(|value| {
+                *value = imported;
+                Ok(())
+            })?;
+            self.curve.save(&dir.join("color_curve.json"))?;
+        }
+        if let Some(outputs) = bundle.outputs {
+            self.filter.replace_overrides(outputs);
+            self.filter
+                .save_overrides(&dir.join("gamma_outputs.json"))?;
+        }
+        replace(bundle.settings)
+    }
+}
+
+pub fn export_file(path: &Path, bundle: &Bundle) -> Result<(), String> {
+    let data = bundle.render(Format::from_path(path))?;
+    settings::write_atomic(path, data.as_bytes())
+}
+
+/// 校验失败时每行一个字段错误
+pub fn import_file(path: &Path) -> Result<Bundle, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("读取配置文件失败: {}", err))?;
+    Bundle::parse(&text, Format::from_path(path)).map_err(|errors| {
+        errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presets::RestCadence;
+    use crate::solar::SolarPhase;
+    use serde_json::json;
+
+    fn sample() -> Bundle {
+        let mut settings = Settings {
+            active_preset: Some("夜读".into()),
+            ..Settings::default()
+        };
+        settings.filter.strength = 55.0;
+        settings.rest.work_minutes = 50;
+        let preset = Preset {
+            name: "夜读".into(),
+            day: SolarPhase {
+                color_temp: 5000.0,
+                strength: 40.0,
+            },
+            night: SolarPhase {
+                color_temp: 3600.0,
+                strength: 60.0,
+            },
+            follow_sun: false,
+            rest: Some(RestCadence {
+                work_minutes: 45,
+                rest_minutes: 5,
+            }),
+            builtin: false,
+        };
+        let solar = SolarSchedule {
+            enabled: true,
+            latitude: 31.2,
+            longitude: 121.5,
+            ..SolarSchedule::default()
+        };
+        let outputs = HashMap::from([(
+            "DP-1".to_string(),
+            FilterSetting {
+                filter_enabled: true,
+                strength: 45.0,
+                color_temp: 4200.0,
+            },
+        )]);
+        Bundle {
+            solar: Some(solar),
+            curve: Some(ColorCurve::default()),
+            outputs: Some(outputs),
+            ..Bundle::new(settings, vec![preset])
+        }
+    }
+
+    #[test]
+    fn round_trips_json_and_toml() {
+        let bundle = sample();
+        for format in [Format::Json, Format::Toml] {
+            let text = bundle.render(format).unwrap();
+            assert_eq!(Bundle::parse(&text, format).unwrap(), bundle);
+        }
+        assert_eq!(Format::from_path(Path::new("team.TOML")), Format::Toml);
+        assert_eq!(Format::from_path(Path::new("team.json")), Format::Json);
+    }
+
+    #[test]
+    fn partial_file_falls_back_to_defaults() {
+        let text = r#"
+            version = 1
+
+            [settings.filter]
+            strength = 60
+        "#;
+        let bundle = Bundle::parse(text, Format::Toml).unwrap();
+        assert_eq!(bundle.settings.filter.strength, 60.0);
+        assert_eq!(
+            bundle.settings.filter.color_temp,
+            Settings::default().filter.color_temp
+        );
+        assert!(bundle.presets.is_empty());
+    }
+
+    #[test]
+    fn reports_every_bad_field() {
+        let text = json!({
+            "version": 1,
+            "settings": {
+                "filter": { "strength": 300 },
+                "rest": { "workMinutes": "很久" },
+                "transitionMs": 20000,
+                "colour": true,
+                "activePreset": "不存在"
+            },
+            "presets": [
+                { "name": "办公", "day": { "colorTemp": 5000, "strength": 40 }, "night": { "colorTemp": 4000, "strength": 50 } },
+                { "name": "冷色", "day": { "colorTemp": 20000, "strength": 40 }, "night": { "colorTemp": 4000, "strength": 50 } }
+            ],
+            "extra": 1
+        })
+        .to_string();
+        let errors = Bundle::parse(&text, Format::Json).unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|err| err.field.as_str()).collect();
+        for expected in [
+            "presets[0].name",
+            "presets[1]",
+            "settings.rest",
+            "settings.colour",
+            "settings.filter.strength",
+            "settings.transitionMs",
+            "settings.activePreset",
+            "extra",
+        ] {
+            assert!(
+                fields.contains(&expected),
+                "缺少 {}: {:?}",
+                expected,
+                fields
+            );
+        }
+    }
+
+    #[test]
+    fn version_one_file_leaves_schedules_alone() {
+        let text = json!({ "version": 1, "settings": {} }).to_string();
+        let bundle = Bundle::parse(&text, Format::Json).unwrap();
+        assert_eq!(bundle.solar, None);
+        assert_eq!(bundle.curve, None);
+        assert_eq!(bundle.outputs, None);
+    }
+
+    #[test]
+    fn reports_bad_schedule_and_output_fields() {
+        let text = json!({
+            "version": 2,
+            "solar": { "latitude": 120 },
+            "curve": {
+                "keyframes": [
+                    { "id": 1, "time": "07:00", "colorTemp": 6500, "strength": 0 },
+                    { "id": 2, "time": "25:00", "colorTemp": 4500, "strength": 40 },
+                    { "id": 3, "time": "07:00", "colorTemp": 3200, "strength": 60 }
+                ]
+            },
+            "outputs": {
+                "DP-1": { "filterEnabled": true, "strength": 300, "colorTemp": 4000 },
+                "DP-2": "暖色"
+            }
+        })
+        .to_string();
+        let errors = Bundle::parse(&text, Format::Json).unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|err| err.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            [
+                "solar",
+                "curve.keyframes[1]",
+                "curve.keyframes[2]",
+                "outputs.DP-1",
+                "outputs.DP-2"
+            ]
+        );
+    }
+
+    #[test]
+    fn stores_round_trip_and_keep_missing_sections() {
+        let dir = std::env::temp_dir().join(format!("huyanba-profile-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let settings = SettingsState::default();
+        let presets = PresetState::default();
+        let solar = SolarState::default();
+        let curve = CurveState::default();
+        let filter = FilterState::default();
+        let stores = Stores {
+            settings: &settings,
+            presets: &presets,
+            solar: &solar,
+            curve: &curve,
+            filter: &filter,
+        };
+
+        let bundle = sample();
+        let applied = stores
+            .apply(&dir, bundle.clone(), |next| {
+                settings.replace(next.clone())?;
+                Ok(next)
+            })
+            .unwrap();
+        assert_eq!(applied, bundle.settings);
+        assert_eq!(stores.bundle(), bundle);
+
+        // 旧版本文件不带日照等三项，本机现状不动
+        let before_curve = curve.curve();
+        stores
+            .apply(&dir, Bundle::new(Settings::default(), Vec::new()), Ok)
+            .unwrap();
+        assert_eq!(solar.schedule(), bundle.solar.unwrap());
+        assert_eq!(curve.curve(), before_curve);
+        assert_eq!(filter.overrides(), bundle.outputs.unwrap());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rejects_missing_or_future_version() {
+        let missing = Bundle::parse("{}", Format::Json).unwrap_err();
+        assert_eq!(missing[0].field, "version");
+        let future = json!({ "version": BUNDLE_VERSION + 1 }).to_string();
+        assert!(Bundle::parse(&future, Format::Json).is_err());
+    }
+}
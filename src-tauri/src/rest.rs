@@ -0,0 +1,1156 @@
+// 休息调度：工作 → 预告 → 休息（可暂停）→ 工作 的状态机，外加并行的 20-20-20 小憩计时，
+// 由后台线程按单调时钟推进，界面被节流或隐藏到托盘时也不会漏掉休息
+use crate::foreground::{Foreground, ForegroundProvider};
+use crate::idle::IdleProvider;
+use crate::settings::Settings;
+use crate::strict::EarlyExit;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::tray::TrayIcon;
+use tauri::{AppHandle, Emitter, Manager};
+
+const TICK_MILLIS: u64 = 500;
+/// 长休息在这段时间内就要开始时跳过小憩，两者不会前后脚出现
+const MICRO_GUARD: Duration = Duration::from_secs(5 * 60);
+const SKIP_LOG: &str = "rest-skips.log";
+
+/// 单调时钟，测试中替换为手动推进的时钟
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RestConfig {
+    pub enabled: bool,
+    pub work: Duration,
+    pub rest: Duration,
+    /// 休息前多久进入预告，为 0 时不预告
+    pub pre_warning: Duration,
+    pub snooze: Duration,
+    /// 同一次休息最多推迟几次
+    pub max_snoozes: u32,
+    /// 空闲超过该时长计为一次休息，None 为关闭
+    pub idle_reset: Option<Duration>,
+    /// 小憩间隔，None 为关闭
+    pub micro_interval: Option<Duration>,
+    pub micro_duration: Duration,
+    pub allow_esc_exit: bool,
+    /// 严格模式下锁屏只能凭口令或当日次数提前结束
+    pub strict: bool,
+}
+
+impl Default for RestConfig {
+    fn default() -> Self {
+        Self::from_settings(&Settings::default())
+    }
+}
+
+impl RestConfig {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            enabled: settings.rest.enabled,
+            work: Duration::from_secs(settings.rest.work_minutes as u64 * 60),
+            rest: Duration::from_secs(settings.rest.rest_minutes as u64 * 60),
+            pre_warning: Duration::from_secs(settings.rest.pre_warning_seconds as u64),
+            snooze: Duration::from_secs(5 * 60),
+            max_snoozes: settings.rest.max_snoozes,
+            idle_reset: (settings.rest.idle_minutes > 0)
+                .then(|| Duration::from_secs(settings.rest.idle_minutes as u64 * 60)),
+            micro_interval: settings
+                .rest
+                .micro
+                .enabled
+                .then(|| Duration::from_secs(settings.rest.micro.interval_minutes as u64 * 60)),
+            micro_duration: Duration::from_secs(settings.rest.micro.duration_seconds as u64),
+            allow_esc_exit: settings.allow_esc_exit,
+            strict: settings.rest.strict.enabled,
+        }
+    }
+}
+
+/// 推迟休息的条件：前台是全屏窗口，或前台进程名包含名单中的某一项
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeferRules {
+    pub fullscreen: bool,
+    pub apps: Vec<String>,
+}
+
+impl DeferRules {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            fullscreen: settings.rest.defer_fullscreen,
+            apps: settings
+                .rest
+                .defer_apps
+                .iter()
+                .map(|app| app.trim().to_lowercase())
+                .filter(|app| !app.is_empty())
+                .collect(),
+        }
+    }
+
+    /// 需要推迟时返回写进日志的原因
+    pub fn reason(&self, foreground: &Foreground) -> Option<String> {
+        let process = foreground.process.to_lowercase();
+        if self.fullscreen && foreground.fullscreen {
+            return Some(if process.is_empty() {
+                "前台全屏窗口".to_string()
+            } else {
+                format!("前台全屏窗口（{}）", foreground.process)
+            });
+        }
+        if process.is_empty() {
+            return None;
+        }
+        self.apps
+            .iter()
+            .any(|app| process.contains(app.as_str()))
+            .then(|| format!("正在使用 {}", foreground.process))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RestPhase {
+    /// 定时休息关闭，只能手动进入休息
+    Disabled,
+    Working {
+        rest_at: Instant,
+    },
+    PreWarning {
+        rest_at: Instant,
+    },
+    Resting {
+        end_at: Instant,
+    },
+    /// 休息倒计时暂停，记录剩余时长
+    Paused {
+        remaining: Duration,
+    },
+    /// 预告阶段选择稍后休息
+    Snoozed {
+        rest_at: Instant,
+    },
+    /// 到点时正在演示或全屏，等条件解除后立即休息
+    Deferred,
+}
+
+impl RestPhase {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Disabled => "disabled",
+            Self::Working { .. } => "working",
+            Self::PreWarning { .. } => "preWarning",
+            Self::Resting { .. } => "resting",
+            Self::Paused { .. } => "paused",
+            Self::Snoozed { .. } => "snoozed",
+            Self::Deferred => "deferred",
+        }
+    }
+}
+
+/// 小憩与长休息并行计时：长休息开始或结束时重新计时
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MicroPhase {
+    Off,
+    Waiting { at: Instant },
+    Active { end_at: Instant },
+}
+
+impl MicroPhase {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Waiting { .. } => "waiting",
+            Self::Active { .. } => "active",
+        }
+    }
+}
+
+/// 发给界面与锁屏窗口的状态，时长为毫秒，由接收方换算成本地时间
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestSnapshot {
+    pub phase: &'static str,
+    pub next_rest_in_ms: Option<u64>,
+    pub rest_remaining_ms: Option<u64>,
+    pub rest_duration_ms: u64,
+    /// 当前处于离开状态，工作计时暂停
+    pub idle: bool,
+    pub snooze_ms: u64,
+    /// 本次休息还能推迟几次
+    pub snoozes_left: u32,
+    /// 推迟中时的原因
+    pub deferred_reason: Option<String>,
+    pub micro_phase: &'static str,
+    pub next_micro_in_ms: Option<u64>,
+    pub micro_remaining_ms: Option<u64>,
+    pub allow_esc_exit: bool,
+    pub strict: bool,
+}
+
+impl RestSnapshot {
+    pub fn is_locked(&self) -> bool {
+        matches!(self.phase, "resting" | "paused")
+    }
+}
+
+pub struct RestScheduler {
+    clock: Box<dyn Clock>,
+    config: RestConfig,
+    phase: RestPhase,
+    idle: bool,
+    /// 本次休息已推迟的次数，预告和锁屏上的稍后合计，回到工作计时才清零
+    snoozes: u32,
+    rules: DeferRules,
+    /// 当前满足的推迟条件，只在休息到点后才检测
+    deferred: Option<String>,
+    micro: MicroPhase,
+}
+
+impl RestScheduler {
+    pub fn new(clock: Box<dyn Clock>, config: RestConfig) -> Self {
+        let mut scheduler = Self {
+            clock,
+            config,
+            phase: RestPhase::Disabled,
+            idle: false,
+            snoozes: 0,
+            rules: DeferRules::default(),
+            deferred: None,
+            micro: MicroPhase::Off,
+        };
+        scheduler.phase = scheduler.work_phase();
+        scheduler.micro = scheduler.fresh_micro();
+        scheduler
+    }
+
+    fn work_phase(&self) -> RestPhase {
+        if self.config.enabled {
+            RestPhase::Working {
+                rest_at: self.clock.now() + self.config.work,
+            }
+        } else {
+            RestPhase::Disabled
+        }
+    }
+
+    fn fresh_micro(&self) -> MicroPhase {
+        match self.config.micro_interval {
+            Some(interval) => MicroPhase::Waiting {
+                at: self.clock.now() + interval,
+            },
+            None => MicroPhase::Off,
+        }
+    }
+
+    /// 定时休息开启且还没开始休息
+    fn before_rest(&self) -> bool {
+        matches!(
+            self.phase,
+            RestPhase::Working { .. }
+                | RestPhase::PreWarning { .. }
+                | RestPhase::Snoozed { .. }
+                | RestPhase::Deferred
+        )
+    }
+
+    /// 休息已到点：还在倒计时但时间已过，或正因推迟条件等待
+    fn rest_due(&self) -> bool {
+        match self.phase {
+            RestPhase::Working { rest_at }
+            | RestPhase::PreWarning { rest_at }
+            | RestPhase::Snoozed { rest_at } => self.clock.now() >= rest_at,
+            RestPhase::Deferred => true,
+            _ => false,
+        }
+    }
+
+    pub fn set_rules(&mut self, rules: DeferRules) {
+        self.rules = rules;
+    }
+
+    fn locked(&self) -> bool {
+        matches!(
+            self.phase,
+            RestPhase::Resting { .. } | RestPhase::Paused { .. }
+        )
+    }
+
+    fn set_phase(&mut self, phase: RestPhase) -> bool {
+        if matches!(phase, RestPhase::Working { .. } | RestPhase::Disabled) {
+            self.snoozes = 0;
+        }
+        // 进入或离开长休息都让小憩从头计时
+        let locked = |phase: &RestPhase| {
+            matches!(phase, RestPhase::Resting { .. } | RestPhase::Paused { .. })
+        };
+        if locked(&phase) != locked(&self.phase) {
+            self.micro = self.fresh_micro();
+        }
+        let changed = self.phase != phase;
+        self.phase = phase;
+        changed
+    }
+
+    /// 更新节奏：工作中改了开关或工作时长就重新计时，休息中不打断
+    pub fn configure(&mut self, config: RestConfig) -> bool {
+        let old = std::mem::replace(&mut self.config, config);
+        if old == config {
+            return false;
+        }
+        if old.micro_interval != config.micro_interval {
+            self.micro = self.fresh_micro();
+        }
+        let restart = old.enabled != config.enabled || old.work != config.work;
+        let working = self.phase == RestPhase::Disabled || self.before_rest();
+        if restart && working {
+            self.set_phase(self.work_phase());
+        }
+        true
+    }
+
+    /// 按当前时间推进，可能连跳多个阶段；返回阶段是否变化
+    pub fn tick(&mut self) -> bool {
+        let now = self.clock.now();
+        let mut changed = false;
+        loop {
+            let next = match self.phase {
+                RestPhase::Working { rest_at }
+                | RestPhase::PreWarning { rest_at }
+                | RestPhase::Snoozed { rest_at }
+                    if now >= rest_at && self.deferred.is_some() =>
+                {
+                    RestPhase::Deferred
+                }
+                RestPhase::Deferred if self.deferred.is_none() => RestPhase::Resting {
+                    end_at: now + self.config.rest,
+                },
+                RestPhase::Working { rest_at } | RestPhase::Snoozed { rest_at }
+                    if now >= rest_at =>
+                {
+                    RestPhase::Resting {
+                        end_at: rest_at + self.config.rest,
+                    }
+                }
+                RestPhase::Working { rest_at } | RestPhase::Snoozed { rest_at }
+                    if !self.config.pre_warning.is_zero()
+                        && now + self.config.pre_warning >= rest_at =>
+                {
+                    RestPhase::PreWarning { rest_at }
+                }
+                RestPhase::PreWarning { rest_at } if now >= rest_at => RestPhase::Resting {
+                    end_at: rest_at + self.config.rest,
+                },
+                RestPhase::Resting { end_at } if now >= end_at => self.work_phase(),
+                _ => break,
+            };
+            changed |= self.set_phase(next);
+        }
+        self.tick_micro(now) | changed
+    }
+
+    /// 长休息已在预告、推迟或快要开始时，不再插入小憩
+    fn long_break_near(&self, now: Instant) -> bool {
+        match self.phase {
+            RestPhase::Disabled => false,
+            RestPhase::Working { rest_at } => rest_at <= now + MICRO_GUARD,
+            _ => true,
+        }
+    }
+
+    fn tick_micro(&mut self, now: Instant) -> bool {
+        let Some(interval) = self.config.micro_interval else {
+            return false;
+        };
+        let next = match self.micro {
+            MicroPhase::Waiting { at } if now >= at => {
+                if self.idle || self.long_break_near(now) {
+                    MicroPhase::Waiting { at: now + interval }
+                } else {
+                    MicroPhase::Active {
+                        end_at: now + self.config.micro_duration,
+                    }
+                }
+            }
+            MicroPhase::Active { end_at } if now >= end_at => {
+                MicroPhase::Waiting { at: now + interval }
+            }
+            _ => return false,
+        };
+        self.micro = next;
+        true
+    }
+
+    /// 提前结束当前小憩
+    pub fn end_micro(&mut self) -> bool {
+        if !matches!(self.micro, MicroPhase::Active { .. }) {
+            return false;
+        }
+        self.micro = self.fresh_micro();
+        true
+    }
+
+    /// 离开超过阈值视为已经休息过：离开期间工作计时停在满额，回来后从头计时。
+    /// 只在进入和结束离开时返回 true
+    pub fn observe_idle(&mut self, idle: Option<Duration>) -> bool {
+        let away = match (self.config.idle_reset, idle) {
+            (Some(threshold), Some(idle)) => idle >= threshold,
+            _ => false,
+        };
+        let working = self.before_rest();
+        if away {
+            self.micro = self.fresh_micro();
+        }
+        if away && working {
+            self.set_phase(self.work_phase());
+            return !std::mem::replace(&mut self.idle, true);
+        }
+        if !away && self.idle {
+            self.idle = false;
+            self.micro = self.fresh_micro();
+            if working {
+                self.set_phase(self.work_phase());
+            }
+            return true;
+        }
+        false
+    }
+
+    pub fn poll_idle(&mut self, provider: &mut dyn IdleProvider) -> bool {
+        let idle = provider.idle_time();
+        self.observe_idle(idle)
+    }
+
+    /// 只在休息到点后看前台窗口；条件变化时返回 true
+    pub fn observe_foreground(&mut self, foreground: Option<Foreground>) -> bool {
+        let reason = if self.rest_due() {
+            foreground.and_then(|foreground| self.rules.reason(&foreground))
+        } else {
+            None
+        };
+        if reason == self.deferred {
+            return false;
+        }
+        self.deferred = reason;
+        true
+    }
+
+    pub fn poll_foreground(&mut self, provider: &mut dyn ForegroundProvider) -> bool {
+        let foreground = if self.rest_due() {
+            provider.foreground()
+        } else {
+            None
+        };
+        self.observe_foreground(foreground)
+    }
+
+    /// 立即休息，定时休息关闭时也可用
+    pub fn start_rest(&mut self) -> bool {
+        if matches!(
+            self.phase,
+            RestPhase::Resting { .. } | RestPhase::Paused { .. }
+        ) {
+            return false;
+        }
+        let end_at = self.clock.now() + self.config.rest;
+        self.set_phase(RestPhase::Resting { end_at })
+    }
+
+    pub fn end_rest(&mut self) -> bool {
+        if !matches!(
+            self.phase,
+            RestPhase::Resting { .. } | RestPhase::Paused { .. }
+        ) {
+            return false;
+        }
+        let next = self.work_phase();
+        self.set_phase(next)
+    }
+
+    pub fn toggle_pause(&mut self) -> bool {
+        let now = self.clock.now();
+        let next = match self.phase {
+            RestPhase::Resting { end_at } => RestPhase::Paused {
+                remaining: end_at.saturating_duration_since(now),
+            },
+            RestPhase::Paused { remaining } => RestPhase::Resting {
+                end_at: now + remaining,
+            },
+            _ => return false,
+        };
+        self.set_phase(next)
+    }
+
+    /// 只在预告阶段有效，把休息推后一个稍后时长；超过次数上限后不再允许
+    pub fn snooze(&mut self) -> bool {
+        let RestPhase::PreWarning { rest_at } = self.phase else {
+            return false;
+        };
+        if self.snoozes >= self.config.max_snoozes {
+            return false;
+        }
+        let rest_at = rest_at.max(self.clock.now()) + self.config.snooze;
+        self.snoozes += 1;
+        self.set_phase(RestPhase::Snoozed { rest_at })
+    }
+
+    /// 锁屏上的稍后：先收起锁屏，delay 后再回来休息；和预告阶段的推迟共用次数上限
+    pub fn snooze_rest(&mut self, delay: Duration) -> bool {
+        if !self.locked() || self.snoozes >= self.config.max_snoozes {
+            return false;
+        }
+        self.snoozes += 1;
+        let rest_at = self.clock.now() + delay;
+        self.set_phase(RestPhase::Snoozed { rest_at })
+    }
+
+    /// 跳过即将到来的这次休息并重新计时，返回跳过前已推迟的次数
+    pub fn skip(&mut self) -> Option<u32> {
+        if !self.before_rest() {
+            return None;
+        }
+        let snoozes = self.snoozes;
+        self.set_phase(self.work_phase());
+        Some(snoozes)
+    }
+
+    pub fn snapshot(&self) -> RestSnapshot {
+        let now = self.clock.now();
+        let millis = |duration: Duration| duration.as_millis() as u64;
+        let (next_rest_in, rest_remaining) = match self.phase {
+            RestPhase::Disabled => (None, None),
+            RestPhase::Working { rest_at }
+            | RestPhase::PreWarning { rest_at }
+            | RestPhase::Snoozed { rest_at } => {
+                (Some(rest_at.saturating_duration_since(now)), None)
+            }
+            RestPhase::Deferred => (Some(Duration::ZERO), None),
+            RestPhase::Resting { end_at } => (None, Some(end_at.saturating_duration_since(now))),
+            RestPhase::Paused { remaining } => (None, Some(remaining)),
+        };
+        let (next_micro_in, micro_remaining) = match self.micro {
+            MicroPhase::Off => (None, None),
+            MicroPhase::Waiting { at } => (Some(at.saturating_duration_since(now)), None),
+            MicroPhase::Active { end_at } => (None, Some(end_at.saturating_duration_since(now))),
+        };
+        RestSnapshot {
+            phase: self.phase.name(),
+            next_rest_in_ms: next_rest_in.map(millis),
+            rest_remaining_ms: rest_remaining.map(millis),
+            rest_duration_ms: millis(self.config.rest),
+            idle: self.idle,
+            snooze_ms: millis(self.config.snooze),
+            snoozes_left: self.config.max_snoozes.saturating_sub(self.snoozes),
+            deferred_reason: self
+                .deferred
+                .clone()
+                .filter(|_| self.phase == RestPhase::Deferred),
+            micro_phase: self.micro.name(),
+            next_micro_in_ms: next_micro_in.map(millis),
+            micro_remaining_ms: micro_remaining.map(millis),
+            allow_esc_exit: self.config.allow_esc_exit && !self.config.strict,
+            strict: self.config.strict,
+        }
+    }
+}
+
+pub struct RestState {
+    scheduler: Mutex<RestScheduler>,
+}
+
+impl Default for RestState {
+    fn default() -> Self {
+        Self {
+            scheduler: Mutex::new(RestScheduler::new(
+                Box::new(SystemClock),
+                RestConfig::default(),
+            )),
+        }
+    }
+}
+
+impl RestState {
+    pub fn with<T>(&self, action: impl FnOnce(&mut RestScheduler) -> T) -> Result<T, String> {
+        let mut scheduler = self.scheduler.lock().map_err(|_| "休息调度被占用")?;
+        Ok(action(&mut scheduler))
+    }
+
+    pub fn snapshot(&self) -> Result<RestSnapshot, String> {
+        self.with(|scheduler| scheduler.snapshot())
+    }
+}
+
+pub fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+/// 状态变化后的统一出口：通知所有窗口，并按是否在休息打开或关闭锁屏
+pub fn sync(app: &AppHandle) -> Result<RestSnapshot, String> {
+    let snapshot = app.state::<RestState>().snapshot()?;
+    let _ = app.emit("rest-state", &snapshot);
+    if snapshot.is_locked() {
+        crate::open_lock_windows(app)?;
+        crate::push_lock_update(app, &snapshot);
+    } else {
+        crate::close_lock_windows(app)?;
+    }
+    match snapshot.micro_remaining_ms {
+        Some(remaining) => crate::open_micro_overlay(app, now_ms() + remaining as i64)?,
+        None => crate::close_micro_overlay(app)?,
+    }
+    Ok(snapshot)
+}
+
+/// 对调度器执行一个动作，阶段变化时同步窗口
+pub fn act(
+    app: &AppHandle,
+    action: impl FnOnce(&mut RestScheduler) -> bool,
+) -> Result<RestSnapshot, String> {
+    let state = app.state::<RestState>();
+    if state.with(action)? {
+        return sync(app);
+    }
+    state.snapshot()
+}
+
+/// 严格模式下经过口令或当日次数验证后的提前结束，每次都写日志
+pub fn exit_early(app: &AppHandle, exit: EarlyExit) -> Result<RestSnapshot, String> {
+    crate::append_app_log(app, &format!("提前结束休息: {}", exit.describe()));
+    act(app, |rest| rest.end_rest())
+}
+
+pub fn snooze(app: &AppHandle) -> Result<RestSnapshot, String> {
+    act(app, |rest| rest.snooze())
+}
+
+/// 跳过本次休息，每次跳过都记到配置目录的 rest-skips.log
+pub fn skip(app: &AppHandle) -> Result<RestSnapshot, String> {
+    let mut skipped = None;
+    let snapshot = act(app, |rest| {
+        skipped = rest.skip();
+        skipped.is_some()
+    })?;
+    if let Some(snoozes) = skipped {
+        record_skip(app, snoozes);
+    }
+    Ok(snapshot)
+}
+
+fn record_skip(app: &AppHandle, snoozes: u32) {
+    crate::append_app_log(app, &format!("跳过本次休息（已推迟 {} 次）", snoozes));
+    let Ok(dir) = crate::ensure_config_dir(app) else {
+        return;
+    };
+    let line = format!(
+        "{}\n",
+        serde_json::json!({ "at": now_ms() / 1000, "snoozes": snoozes })
+    );
+    if let Ok(mut file) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(SKIP_LOG))
+    {
+        let _ = file.write_all(line.as_bytes());
+    }
+}
+
+/// 预告阶段托盘提示显示倒计时，其余时候恢复默认
+fn tray_tooltip(snapshot: &RestSnapshot) -> String {
+    match (snapshot.phase, snapshot.next_rest_in_ms) {
+        ("preWarning", Some(ms)) => format!("护眼吧 · {} 秒后休息", ms.div_ceil(1000)),
+        _ => "护眼吧".to_string(),
+    }
+}
+
+fn warn_break(app: &AppHandle, snapshot: &RestSnapshot) {
+    let seconds = snapshot.next_rest_in_ms.unwrap_or(0).div_ceil(1000);
+    let snooze = (snapshot.snoozes_left > 0).then_some(Duration::from_millis(snapshot.snooze_ms));
+    if let Err(err) = crate::notification::show_break_warning(app, seconds, snooze) {
+        crate::append_app_log(app, &format!("休息预告通知失败: {}", err));
+    }
+}
+
+pub fn run_rest_scheduler(app: AppHandle) {
+    let mut idle = crate::idle::default_provider();
+    crate::append_app_log(&app, &format!("空闲检测: {}", idle.name()));
+    let mut foreground = crate::foreground::default_provider();
+    crate::append_app_log(&app, &format!("前台窗口检测: {}", foreground.name()));
+    let mut last_phase = "";
+    let mut tooltip = String::new();
+    loop {
+        let changed = app
+            .state::<RestState>()
+            .with(|scheduler| {
+                scheduler.poll_idle(idle.as_mut())
+                    | scheduler.poll_foreground(foreground.as_mut())
+                    | scheduler.tick()
+            })
+            .unwrap_or(false);
+        let snapshot = if changed {
+            let synced = sync(&app);
+            match &synced {
+                Ok(snapshot) if snapshot.idle => {
+                    crate::append_app_log(&app, "检测到离开，计为一次休息")
+                }
+                Ok(RestSnapshot {
+                    deferred_reason: Some(reason),
+                    ..
+                }) => crate::append_app_log(&app, &format!("推迟休息: {}", reason)),
+                Ok(snapshot) if last_phase == "deferred" && snapshot.is_locked() => {
+                    crate::append_app_log(&app, "推迟条件解除，开始休息")
+                }
+                Ok(snapshot) => crate::append_app_log(
+                    &app,
+                    &format!(
+                        "休息状态: {} 小憩: {}",
+                        snapshot.phase, snapshot.micro_phase
+                    ),
+                ),
+                Err(err) => crate::append_app_log(&app, &format!("休息调度失败: {}", err)),
+            }
+            synced
+        } else {
+            app.state::<RestState>().snapshot()
+        };
+        if let Ok(snapshot) = snapshot {
+            // 锁屏倒计时由这里统一推送，不依赖主窗口
+            if snapshot.is_locked() {
+                crate::check_lock_windows(&app, snapshot.strict);
+                crate::push_lock_update(&app, &snapshot);
+            }
+            if snapshot.phase == "preWarning" && last_phase != "preWarning" {
+                warn_break(&app, &snapshot);
+            }
+            last_phase = snapshot.phase;
+            let next = tray_tooltip(&snapshot);
+            if next != tooltip {
+                if let Some(tray) = app.try_state::<TrayIcon>() {
+                    let _ = tray.set_tooltip(Some(next.as_str()));
+                }
+                tooltip = next;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(TICK_MILLIS));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::foreground::mock::MockForeground;
+    use crate::idle::mock::MockIdle;
+    use std::sync::Arc;
+
+    #[derive(Clone)]
+    struct ManualClock(Arc<Mutex<Instant>>);
+
+    impl ManualClock {
+        fn new() -> Self {
+            Self(Arc::new(Mutex::new(Instant::now())))
+        }
+
+        fn advance(&self, secs: u64) {
+            *self.0.lock().unwrap() += Duration::from_secs(secs);
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    fn config() -> RestConfig {
+        RestConfig {
+            enabled: true,
+            work: Duration::from_secs(30 * 60),
+            rest: Duration::from_secs(60),
+            pre_warning: Duration::from_secs(30),
+            snooze: Duration::from_secs(5 * 60),
+            max_snoozes: 2,
+            idle_reset: Some(Duration::from_secs(5 * 60)),
+            micro_interval: None,
+            micro_duration: Duration::from_secs(20),
+            allow_esc_exit: true,
+            strict: false,
+        }
+    }
+
+    fn scheduler(config: RestConfig) -> (RestScheduler, ManualClock) {
+        let clock = ManualClock::new();
+        (RestScheduler::new(Box::new(clock.clone()), config), clock)
+    }
+
+    #[test]
+    fn runs_full_cycle() {
+        let (mut rest, clock) = scheduler(config());
+        assert_eq!(rest.snapshot().phase, "working");
+        assert_eq!(rest.snapshot().next_rest_in_ms, Some(30 * 60 * 1000));
+
+        clock.advance(29 * 60);
+        assert!(!rest.tick());
+        clock.advance(40);
+        assert!(rest.tick());
+        assert_eq!(rest.snapshot().phase, "preWarning");
+
+        clock.advance(20);
+        assert!(rest.tick());
+        assert!(rest.snapshot().is_locked());
+        assert_eq!(rest.snapshot().rest_remaining_ms, Some(60 * 1000));
+
+        clock.advance(60);
+        assert!(rest.tick());
+        assert_eq!(rest.snapshot().phase, "working");
+        assert_eq!(rest.snapshot().next_rest_in_ms, Some(30 * 60 * 1000));
+    }
+
+    #[test]
+    fn long_gap_skips_straight_through() {
+        let (mut rest, clock) = scheduler(config());
+        clock.advance(30 * 60 + 30);
+        assert!(rest.tick());
+        assert_eq!(rest.snapshot().phase, "resting");
+        // 休息按计划时刻起算，迟到的 30 秒计入休息
+        assert_eq!(rest.snapshot().rest_remaining_ms, Some(30 * 1000));
+    }
+
+    #[test]
+    fn pause_freezes_remaining_time() {
+        let (mut rest, clock) = scheduler(config());
+        assert!(rest.start_rest());
+        clock.advance(20);
+        assert!(rest.toggle_pause());
+        clock.advance(600);
+        assert!(!rest.tick());
+        assert_eq!(rest.snapshot().phase, "paused");
+        assert_eq!(rest.snapshot().rest_remaining_ms, Some(40 * 1000));
+
+        assert!(rest.toggle_pause());
+        clock.advance(40);
+        assert!(rest.tick());
+        assert_eq!(rest.snapshot().phase, "working");
+    }
+
+    #[test]
+    fn snooze_only_from_pre_warning() {
+        let (mut rest, clock) = scheduler(config());
+        assert!(!rest.snooze());
+        clock.advance(30 * 60 - 10);
+        rest.tick();
+        assert!(rest.snooze());
+        assert_eq!(rest.snapshot().phase, "snoozed");
+        assert_eq!(rest.snapshot().next_rest_in_ms, Some(310 * 1000));
+
+        clock.advance(290);
+        assert!(rest.tick());
+        assert_eq!(rest.snapshot().phase, "preWarning");
+        clock.advance(20);
+        rest.tick();
+        assert_eq!(rest.snapshot().phase, "resting");
+    }
+
+    #[test]
+    fn disabled_schedule_allows_manual_rest() {
+        let (mut rest, clock) = scheduler(RestConfig {
+            enabled: false,
+            ..config()
+        });
+        clock.advance(24 * 60 * 60);
+        assert!(!rest.tick());
+        assert_eq!(rest.snapshot().phase, "disabled");
+
+        assert!(rest.start_rest());
+        assert!(rest.end_rest());
+        assert_eq!(rest.snapshot().phase, "disabled");
+        assert!(!rest.end_rest());
+    }
+
+    #[test]
+    fn configure_restarts_work_timer_but_not_rest() {
+        let (mut rest, clock) = scheduler(config());
+        clock.advance(10 * 60);
+        assert!(rest.configure(RestConfig {
+            work: Duration::from_secs(45 * 60),
+            ..config()
+        }));
+        assert_eq!(rest.snapshot().next_rest_in_ms, Some(45 * 60 * 1000));
+        assert!(!rest.configure(RestConfig {
+            work: Duration::from_secs(45 * 60),
+            ..config()
+        }));
+
+        rest.start_rest();
+        rest.configure(RestConfig {
+            enabled: false,
+            ..config()
+        });
+        assert_eq!(rest.snapshot().phase, "resting");
+        rest.end_rest();
+        assert_eq!(rest.snapshot().phase, "disabled");
+    }
+
+    #[test]
+    fn long_idle_counts_as_rest() {
+        let (mut rest, clock) = scheduler(config());
+        let mut idle = MockIdle::default();
+        clock.advance(25 * 60);
+        idle.set(Some(Duration::from_secs(4 * 60)));
+        assert!(!rest.poll_idle(&mut idle));
+
+        clock.advance(60);
+        idle.set(Some(Duration::from_secs(5 * 60)));
+        assert!(rest.poll_idle(&mut idle));
+        assert!(rest.snapshot().idle);
+        // 离开期间到了原定休息时刻也不会锁屏
+        clock.advance(10 * 60);
+        idle.set(Some(Duration::from_secs(15 * 60)));
+        assert!(!rest.poll_idle(&mut idle));
+        assert!(!rest.tick());
+
+        idle.set(Some(Duration::from_secs(1)));
+        assert!(rest.poll_idle(&mut idle));
+        let snapshot = rest.snapshot();
+        assert_eq!((snapshot.phase, snapshot.idle), ("working", false));
+        assert_eq!(snapshot.next_rest_in_ms, Some(30 * 60 * 1000));
+    }
+
+    #[test]
+    fn idle_is_ignored_when_disabled_or_unknown() {
+        let (mut rest, _clock) = scheduler(RestConfig {
+            idle_reset: None,
+            ..config()
+        });
+        assert!(!rest.observe_idle(Some(Duration::from_secs(3600))));
+        let (mut rest, _clock) = scheduler(config());
+        assert!(!rest.observe_idle(None));
+        rest.start_rest();
+        assert!(!rest.observe_idle(Some(Duration::from_secs(3600))));
+        assert_eq!(rest.snapshot().phase, "resting");
+    }
+
+    #[test]
+    fn snooze_limit_resets_after_rest() {
+        let (mut rest, clock) = scheduler(config());
+        clock.advance(30 * 60 - 10);
+        rest.tick();
+        assert!(rest.snooze());
+        clock.advance(5 * 60);
+        rest.tick();
+        assert!(rest.snooze());
+        assert_eq!(rest.snapshot().snoozes_left, 0);
+        clock.advance(5 * 60);
+        rest.tick();
+        assert_eq!(rest.snapshot().phase, "preWarning");
+        assert!(!rest.snooze());
+
+        clock.advance(30);
+        rest.tick();
+        assert_eq!(rest.snapshot().phase, "resting");
+        assert_eq!(rest.snapshot().snoozes_left, 0);
+
+        clock.advance(60);
+        rest.tick();
+        assert_eq!(rest.snapshot().phase, "working");
+        assert_eq!(rest.snapshot().snoozes_left, 2);
+    }
+
+    #[test]
+    fn skip_restarts_work_timer() {
+        let (mut rest, clock) = scheduler(config());
+        clock.advance(30 * 60 - 10);
+        rest.tick();
+        assert!(rest.snooze());
+        clock.advance(60);
+        assert_eq!(rest.skip(), Some(1));
+        let snapshot = rest.snapshot();
+        assert_eq!(snapshot.phase, "working");
+        assert_eq!(snapshot.next_rest_in_ms, Some(30 * 60 * 1000));
+        assert_eq!(snapshot.snoozes_left, 2);
+
+        rest.start_rest();
+        assert_eq!(rest.skip(), None);
+    }
+
+    #[test]
+    fn tooltip_counts_down_during_pre_warning() {
+        let (mut rest, clock) = scheduler(config());
+        assert_eq!(tray_tooltip(&rest.snapshot()), "护眼吧");
+        clock.advance(30 * 60 - 10);
+        rest.tick();
+        assert_eq!(tray_tooltip(&rest.snapshot()), "护眼吧 · 10 秒后休息");
+    }
+
+    #[test]
+    fn lock_screen_snooze_shares_the_limit() {
+        let (mut rest, clock) = scheduler(config());
+        assert!(!rest.snooze_rest(Duration::from_secs(60)));
+        // 预告阶段推迟一次，锁屏上只剩一次
+        clock.advance(30 * 60 - 10);
+        rest.tick();
+        assert!(rest.snooze());
+        clock.advance(5 * 60 + 30);
+        rest.tick();
+        assert!(rest.snapshot().is_locked());
+        assert_eq!(rest.snapshot().snoozes_left, 1);
+        assert!(rest.snooze_rest(Duration::from_secs(60)));
+        assert_eq!(rest.snapshot().phase, "snoozed");
+
+        clock.advance(60);
+        rest.tick();
+        assert!(rest.snapshot().is_locked());
+        assert_eq!(rest.snapshot().snoozes_left, 0);
+        assert!(!rest.snooze_rest(Duration::from_secs(60)));
+
+        // 休息结束回到工作后次数恢复
+        clock.advance(60);
+        rest.tick();
+        assert_eq!(rest.snapshot().phase, "working");
+        rest.start_rest();
+        assert_eq!(rest.snapshot().snoozes_left, 2);
+    }
+
+    #[test]
+    fn strict_mode_hides_esc_exit() {
+        let (mut rest, _clock) = scheduler(RestConfig {
+            strict: true,
+            ..config()
+        });
+        rest.start_rest();
+        let snapshot = rest.snapshot();
+        assert!(snapshot.is_locked() && snapshot.strict);
+        assert!(!snapshot.allow_esc_exit);
+    }
+
+    #[test]
+    fn fullscreen_or_listed_app_defers_until_clear() {
+        let (mut rest, clock) = scheduler(config());
+        rest.set_rules(DeferRules {
+            fullscreen: true,
+            apps: vec!["zoom".into()],
+        });
+        let mut window = MockForeground::default();
+        window.set("firefox", true);
+        clock.advance(30 * 60 - 10);
+        // 未到点不检测
+        assert!(!rest.poll_foreground(&mut window));
+        rest.tick();
+
+        clock.advance(10);
+        assert!(rest.poll_foreground(&mut window));
+        assert!(rest.tick());
+        let snapshot = rest.snapshot();
+        assert_eq!(snapshot.phase, "deferred");
+        assert_eq!(
+            snapshot.deferred_reason.as_deref(),
+            Some("前台全屏窗口（firefox）")
+        );
+
+        clock.advance(20 * 60);
+        window.set("Zoom", false);
+        assert!(rest.poll_foreground(&mut window));
+        assert!(!rest.tick());
+        assert_eq!(
+            rest.snapshot().deferred_reason.as_deref(),
+            Some("正在使用 Zoom")
+        );
+
+        window.clear();
+        assert!(rest.poll_foreground(&mut window));
+        assert!(rest.tick());
+        let snapshot = rest.snapshot();
+        assert_eq!(snapshot.phase, "resting");
+        // 从条件解除时起算完整休息时长
+        assert_eq!(snapshot.rest_remaining_ms, Some(60 * 1000));
+    }
+
+    #[test]
+    fn defer_rules_ignore_unlisted_windows() {
+        let rules = DeferRules::from_settings(&Settings::default());
+        let window = |process: &str, fullscreen| Foreground {
+            process: process.into(),
+            fullscreen,
+        };
+        assert!(rules.reason(&window("obs64", false)).is_some());
+        assert!(rules.reason(&window("POWERPNT", false)).is_some());
+        assert!(rules.reason(&window("code", false)).is_none());
+        assert!(rules.reason(&window("", false)).is_none());
+        let off = DeferRules {
+            fullscreen: false,
+            ..rules
+        };
+        assert!(off.reason(&window("mpv", true)).is_none());
+    }
+
+    fn micro_config() -> RestConfig {
+        RestConfig {
+            work: Duration::from_secs(45 * 60),
+            micro_interval: Some(Duration::from_secs(20 * 60)),
+            ..config()
+        }
+    }
+
+    #[test]
+    fn micro_break_skips_when_long_break_is_near() {
+        let (mut rest, clock) = scheduler(micro_config());
+        clock.advance(20 * 60);
+        assert!(rest.tick());
+        let snapshot = rest.snapshot();
+        assert_eq!(
+            (snapshot.phase, snapshot.micro_phase),
+            ("working", "active")
+        );
+        assert_eq!(snapshot.micro_remaining_ms, Some(20 * 1000));
+
+        clock.advance(20);
+        rest.tick();
+        assert_eq!(rest.snapshot().micro_phase, "waiting");
+        // 下一次小憩在 40:20，离 45:00 的长休息不到 5 分钟，直接跳过
+        clock.advance(20 * 60);
+        rest.tick();
+        assert_eq!(rest.snapshot().micro_phase, "waiting");
+
+        clock.advance(4 * 60 + 40);
+        rest.tick();
+        assert_eq!(rest.snapshot().phase, "resting");
+        clock.advance(60);
+        rest.tick();
+        let snapshot = rest.snapshot();
+        assert_eq!(snapshot.phase, "working");
+        assert_eq!(snapshot.next_micro_in_ms, Some(20 * 60 * 1000));
+    }
+
+    #[test]
+    fn micro_break_runs_without_long_breaks_and_ends_early() {
+        let (mut rest, clock) = scheduler(RestConfig {
+            enabled: false,
+            ..micro_config()
+        });
+        clock.advance(20 * 60);
+        rest.tick();
+        assert_eq!(rest.snapshot().micro_phase, "active");
+        clock.advance(5);
+        assert!(rest.end_micro());
+        assert!(!rest.end_micro());
+        assert_eq!(rest.snapshot().next_micro_in_ms, Some(20 * 60 * 1000));
+
+        // 离开期间一直顺延
+        clock.advance(20 * 60);
+        rest.observe_idle(Some(Duration::from_secs(10 * 60)));
+        assert!(!rest.tick());
+        assert_eq!(rest.snapshot().micro_phase, "waiting");
+    }
+}
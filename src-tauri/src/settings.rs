@@ -0,0 +1,483 @@
+// 持久化设置：配置目录下的 settings.json，带版本号，读取时逐级迁移到当前版本
+use crate::color::ColorAlgorithm;
+use crate::filter::FilterSetting;
+use crate::gamma::ToneCurve;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const SETTINGS_FILE: &str = "settings.json";
+pub const CURRENT_VERSION: u32 = 1;
+
+/// 第 n 项把版本 n 的数据升级到 n + 1
+const MIGRATIONS: [fn(&mut Map<String, Value>); CURRENT_VERSION as usize] = [migrate_v0_to_v1];
+
+/// 20-20-20 小憩：每隔一段时间看远处几十秒，只弹小窗不锁屏
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MicroBreakSettings {
+    pub enabled: bool,
+    pub interval_minutes: u32,
+    pub duration_seconds: u32,
+}
+
+impl Default for MicroBreakSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 20,
+            duration_seconds: 20,
+        }
+    }
+}
+
+/// 严格休息：锁屏不能随手关掉，只能凭应急口令或每日有限次数提前结束
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StrictSettings {
+    pub enabled: bool,
+    /// 应急口令的 SHA-256，不保存明文
+    pub passphrase_hash: Option<String>,
+    pub daily_overrides: u32,
+}
+
+impl Default for StrictSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            passphrase_hash: None,
+            daily_overrides: 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RestSettings {
+    pub enabled: bool,
+    pub work_minutes: u32,
+    pub rest_minutes: u32,
+    /// 离开电脑超过该分钟数计为一次休息，0 为关闭
+    pub idle_minutes: u32,
+    /// 休息前多少秒发出预告通知，0 为不预告
+    pub pre_warning_seconds: u32,
+    /// 每次休息最多可推迟几次
+    pub max_snoozes: u32,
+    /// 前台有全屏窗口时推迟休息
+    pub defer_fullscreen: bool,
+    /// 前台进程名包含其中任一项时推迟休息，不区分大小写
+    pub defer_apps: Vec<String>,
+    pub micro: MicroBreakSettings,
+    pub strict: StrictSettings,
+    /// 锁屏窗口比显示器四周各多出的逻辑像素，盖住边缘缝隙
+    pub lock_overscan: u32,
+}
+
+impl Default for RestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            work_minutes: 30,
+            rest_minutes: 1,
+            idle_minutes: 5,
+            pre_warning_seconds: 60,
+            max_snoozes: 2,
+            defer_fullscreen: true,
+            defer_apps: vec!["zoom".into(), "obs".into(), "powerpnt".into()],
+            micro: MicroBreakSettings::default(),
+            strict: StrictSettings::default(),
+            lock_overscan: 200,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub version: u32,
+    pub filter: FilterSetting,
+    pub rest: RestSettings,
+    pub allow_esc_exit: bool,
+    pub transition_ms: u64,
+    pub color_algorithm: ColorAlgorithm,
+    pub tone: ToneCurve,
+    pub active_preset: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            filter: FilterSetting {
+                filter_enabled: true,
+                ..FilterSetting::default()
+            },
+            rest: RestSettings::default(),
+            allow_esc_exit: true,
+            transition_ms: 800,
+            color_algorithm: ColorAlgorithm::default(),
+            tone: ToneCurve::default(),
+            active_preset: None,
+        }
+    }
+}
+
+/// 导入或校验时定位到具体字段的错误，字段名与 JSON 中的写法一致
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl Settings {
+    pub fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if !(0.0..=100.0).contains(&self.filter.strength) {
+            errors.push(FieldError::new("filter.strength", "强度需在 0 到 100 之间"));
+        }
+        if !(1000.0..=10000.0).contains(&self.filter.color_temp) {
+            errors.push(FieldError::new(
+                "filter.colorTemp",
+                "色温需在 1000K 到 10000K 之间",
+            ));
+        }
+        if !(1..=240).contains(&self.rest.work_minutes) {
+            errors.push(FieldError::new(
+                "rest.workMinutes",
+                "工作时长需在 1 到 240 分钟之间",
+            ));
+        }
+        if !(1..=60).contains(&self.rest.rest_minutes) {
+            errors.push(FieldError::new(
+                "rest.restMinutes",
+                "休息时长需在 1 到 60 分钟之间",
+            ));
+        }
+        if self.rest.idle_minutes > 120 {
+            errors.push(FieldError::new(
+                "rest.idleMinutes",
+                "离开判定需在 0 到 120 分钟之间",
+            ));
+        }
+        if self.rest.pre_warning_seconds > 600 {
+            errors.push(FieldError::new(
+                "rest.preWarningSeconds",
+                "休息预告需在 0 到 600 秒之间",
+            ));
+        }
+        if self.rest.max_snoozes > 10 {
+            errors.push(FieldError::new("rest.maxSnoozes", "推迟次数不能超过 10 次"));
+        }
+        if self.rest.defer_apps.len() > 50 {
+            errors.push(FieldError::new("rest.deferApps", "推迟名单最多 50 项"));
+        } else if self.rest.defer_apps.iter().any(|app| app.trim().is_empty()) {
+            errors.push(FieldError::new("rest.deferApps", "进程名不能为空"));
+        }
+        if !(5..=120).contains(&self.rest.micro.interval_minutes) {
+            errors.push(FieldError::new(
+                "rest.micro.intervalMinutes",
+                "小憩间隔需在 5 到 120 分钟之间",
+            ));
+        }
+        if !(10..=120).contains(&self.rest.micro.duration_seconds) {
+            errors.push(FieldError::new(
+                "rest.micro.durationSeconds",
+                "小憩时长需在 10 到 120 秒之间",
+            ));
+        }
+        if self.rest.lock_overscan > 1000 {
+            errors.push(FieldError::new(
+                "rest.lockOverscan",
+                "锁屏外扩不能超过 1000 像素",
+            ));
+        }
+        if self.rest.strict.daily_overrides > 20 {
+            errors.push(FieldError::new(
+                "rest.strict.dailyOverrides",
+                "每日提前结束次数不能超过 20 次",
+            ));
+        }
+        let valid_hash =
+            |hash: &String| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit());
+        if !self.rest.strict.passphrase_hash.iter().all(valid_hash) {
+            errors.push(FieldError::new(
+                "rest.strict.passphraseHash",
+                "口令摘要格式错误",
+            ));
+        }
+        if self.transition_ms > 10_000 {
+            errors.push(FieldError::new("transitionMs", "渐变时长不能超过 10 秒"));
+        }
+        if let Err(err) = self.tone.validate() {
+            errors.push(FieldError::new("tone", err));
+        }
+        errors
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self.field_errors().into_iter().next() {
+            Some(err) => Err(err.message),
+            None => Ok(()),
+        }
+    }
+
+    /// 把 JSON 对象按字段合并进当前设置，未出现的字段保持不变
+    pub fn merged(&self, patch: &Value) -> Result<Settings, String> {
+        if !patch.is_object() {
+            return Err("设置必须是 JSON 对象".into());
+        }
+        let mut value = serde_json::to_value(self).map_err(|err| err.to_string())?;
+        merge(&mut value, patch);
+        let mut next: Settings = serde_json::from_value(value).map_err(|err| err.to_string())?;
+        next.version = CURRENT_VERSION;
+        next.validate()?;
+        Ok(next)
+    }
+}
+
+/// 对象逐键递归合并，其他类型直接覆盖
+pub fn merge(target: &mut Value, patch: &Value) {
+    match (target, patch) {
+        (Value::Object(target), Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+        (target, patch) => *target = patch.clone(),
+    }
+}
+
+/// 早期没有 version 字段的文件，结构与 v1 相同
+fn migrate_v0_to_v1(_settings: &mut Map<String, Value>) {}
+
+/// 逐级迁移到当前版本；比程序更新的版本拒绝读取，避免降级后丢字段
+pub fn upgrade(mut value: Value) -> Result<Value, String> {
+    let object = value.as_object_mut().ok_or("设置文件格式错误")?;
+    let mut version = object.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+    if version > CURRENT_VERSION {
+        return Err(format!("设置文件版本 {} 高于当前程序支持的版本", version));
+    }
+    while version < CURRENT_VERSION {
+        MIGRATIONS[version as usize](object);
+        version += 1;
+        object.insert("version".into(), Value::from(version));
+    }
+    Ok(value)
+}
+
+pub fn migrate(value: Value) -> Result<Settings, String> {
+    let settings: Settings =
+        serde_json::from_value(upgrade(value)?).map_err(|err| err.to_string())?;
+    settings.validate()?;
+    Ok(settings)
+}
+
+/// 先写同目录临时文件并落盘，再改名覆盖，写到一半被杀也不会留下残缺文件
+pub fn write_atomic(path: &Path, data: &[u8]) -> Result<(), String> {
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or("无效的文件路径")?;
+    let tmp = path.with_file_name(format!("{}.tmp", file_name));
+    let mut file = File::create(&tmp).map_err(|err| err.to_string())?;
+    file.write_all(data).map_err(|err| err.to_string())?;
+    file.sync_all().map_err(|err| err.to_string())?;
+    drop(file);
+    fs::rename(&tmp, path).map_err(|err| err.to_string())
+}
+
+#[derive(Default)]
+pub struct SettingsState {
+    settings: Mutex<Settings>,
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl SettingsState {
+    pub fn get(&self) -> Settings {
+        self.settings
+            .lock()
+            .map(|value| value.clone())
+            .unwrap_or_default()
+    }
+
+    /// 读取失败时保留默认值并返回原因，原文件先备份为 .bak 再被后续保存覆盖
+    pub fn load(&self, path: PathBuf) -> Result<(), String> {
+        if let Ok(mut current) = self.path.lock() {
+            *current = Some(path.clone());
+        }
+        let Ok(data) = fs::read_to_string(&path) else {
+            return Ok(());
+        };
+        let loaded = serde_json::from_str::<Value>(&data)
+            .map_err(|err| err.to_string())
+            .and_then(|value| {
+                let stale =
+                    value.get("version").and_then(Value::as_u64) != Some(CURRENT_VERSION as u64);
+                migrate(value).map(|settings| (settings, stale))
+            });
+        match loaded {
+            Ok((settings, stale)) => {
+                self.replace(settings)?;
+                // 迁移后立即写回，下次启动不用再迁移
+                if stale {
+                    self.save()?;
+                }
+                Ok(())
+            }
+            Err(err) => {
+                let _ = fs::copy(&path, path.with_extension("json.bak"));
+                Err(err)
+            }
+        }
+    }
+
+    pub fn replace(&self, settings: Settings) -> Result<(), String> {
+        let mut current = self.settings.lock().map_err(|_| "设置被占用")?;
+        *current = settings;
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        self.write(&self.get())
+    }
+
+    fn write(&self, settings: &Settings) -> Result<(), String> {
+        let Some(path) = self.path.lock().ok().and_then(|value| value.clone()) else {
+            return Ok(());
+        };
+        let data = serde_json::to_string_pretty(settings).map_err(|err| err.to_string())?;
+        write_atomic(&path, data.as_bytes())
+    }
+
+    /// 修改并立即保存；读改写全程持锁，同时进行的两次修改不会互相覆盖
+    pub fn modify(&self, change: impl FnOnce(&mut Settings)) -> Result<Settings, String> {
+        let mut current = self.settings.lock().map_err(|_| "设置被占用")?;
+        let mut next = current.clone();
+        change(&mut next);
+        next.validate()?;
+        self.write(&next)?;
+        *current = next.clone();
+        Ok(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "huyanba-settings-test-{}-{}",
+            std::process::id(),
+            name
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(SETTINGS_FILE)
+    }
+
+    #[test]
+    fn unversioned_file_is_migrated_and_rewritten() {
+        let path = temp_path("migrate");
+        fs::write(
+            &path,
+            json!({ "rest": { "workMinutes": 45 }, "allowEscExit": false }).to_string(),
+        )
+        .unwrap();
+        let state = SettingsState::default();
+        state.load(path.clone()).unwrap();
+        let settings = state.get();
+        assert_eq!(settings.version, CURRENT_VERSION);
+        assert_eq!(settings.rest.work_minutes, 45);
+        assert_eq!(settings.rest.rest_minutes, 1);
+        assert!(!settings.allow_esc_exit);
+
+        let saved: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["version"], json!(CURRENT_VERSION));
+    }
+
+    #[test]
+    fn newer_version_is_rejected_and_backed_up() {
+        let path = temp_path("newer");
+        fs::write(&path, json!({ "version": CURRENT_VERSION + 1 }).to_string()).unwrap();
+        let state = SettingsState::default();
+        assert!(state.load(path.clone()).is_err());
+        assert_eq!(state.get(), Settings::default());
+        assert!(path.with_extension("json.bak").exists());
+    }
+
+    #[test]
+    fn merge_patch_keeps_other_fields() {
+        let settings = Settings::default();
+        let next = settings
+            .merged(&json!({ "filter": { "strength": 55.0 }, "transitionMs": 0 }))
+            .unwrap();
+        assert_eq!(next.filter.strength, 55.0);
+        assert_eq!(next.filter.color_temp, settings.filter.color_temp);
+        assert_eq!(next.transition_ms, 0);
+        assert!(settings
+            .merged(&json!({ "rest": { "workMinutes": 0 } }))
+            .is_err());
+        assert!(settings.merged(&json!([1, 2])).is_err());
+    }
+
+    #[test]
+    fn concurrent_modifies_keep_both_changes() {
+        let state = std::sync::Arc::new(SettingsState::default());
+        let handles: Vec<_> = (0..2)
+            .map(|worker| {
+                let state = state.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        state
+                            .modify(|settings| {
+                                if worker == 0 {
+                                    settings.transition_ms += 10;
+                                } else {
+                                    settings.rest.lock_overscan += 1;
+                                }
+                            })
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        let defaults = Settings::default();
+        let settings = state.get();
+        assert_eq!(settings.transition_ms, defaults.transition_ms + 1000);
+        assert_eq!(
+            settings.rest.lock_overscan,
+            defaults.rest.lock_overscan + 100
+        );
+    }
+
+    #[test]
+    fn atomic_write_replaces_existing_file() {
+        let path = temp_path("atomic");
+        fs::write(&path, "old").unwrap();
+        write_atomic(&path, b"new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert!(!path.with_file_name("settings.json.tmp").exists());
+    }
+}
@@ -0,0 +1,383 @@
+// 日出日落自动色温：NOAA 太阳位置算法，按太阳高度角在白天/夜间设置之间渐变
+use crate::curve::CurveState;
+use crate::filter::FilterSetting;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// 民用晨昏蒙影：太阳位于地平线下 6°
+pub const CIVIL_TWILIGHT_ELEVATION: f64 = -6.0;
+/// 太阳高于该高度角视为完全白天
+const DAY_ELEVATION: f64 = 3.0;
+const TICK_SECS: u64 = 60;
+const SECS_PER_DAY: f64 = 86400.0;
+
+fn julian_century(unix_secs: f64) -> f64 {
+    let julian_day = unix_secs / SECS_PER_DAY + 2440587.5;
+    (julian_day - 2451545.0) / 36525.0
+}
+
+/// 返回 (赤纬°, 时差分钟)
+fn declination_and_equation_of_time(unix_secs: f64) -> (f64, f64) {
+    let jc = julian_century(unix_secs);
+    let mean_long = (280.46646 + jc * (36000.76983 + jc * 0.0003032)).rem_euclid(360.0);
+    let mean_anom = 357.52911 + jc * (35999.05029 - 0.0001537 * jc);
+    let eccent = 0.016708634 - jc * (0.000042037 + 0.0000001267 * jc);
+    let m = mean_anom.to_radians();
+    let center = m.sin() * (1.914602 - jc * (0.004817 + 0.000014 * jc))
+        + (2.0 * m).sin() * (0.019993 - 0.000101 * jc)
+        + (3.0 * m).sin() * 0.000289;
+    let omega = (125.04 - 1934.136 * jc).to_radians();
+    let apparent_long = mean_long + center - 0.00569 - 0.00478 * omega.sin();
+    let mean_obliq =
+        23.0 + (26.0 + (21.448 - jc * (46.815 + jc * (0.00059 - jc * 0.001813))) / 60.0) / 60.0;
+    let obliq = (mean_obliq + 0.00256 * omega.cos()).to_radians();
+    let declination = (obliq.sin() * apparent_long.to_radians().sin()).asin();
+
+    let y = (obliq / 2.0).tan().powi(2);
+    let l0 = mean_long.to_radians();
+    let eq_time = 4.0
+        * (y * (2.0 * l0).sin() - 2.0 * eccent * m.sin()
+            + 4.0 * eccent * y * m.sin() * (2.0 * l0).cos()
+            - 0.5 * y * y * (4.0 * l0).sin()
+            - 1.25 * eccent * eccent * (2.0 * m).sin())
+        .to_degrees();
+    (declination.to_degrees(), eq_time)
+}
+
+/// 太阳高度角（度），未做大气折射修正
+pub fn solar_elevation(unix_secs: f64, latitude: f64, longitude: f64) -> f64 {
+    let (declination, eq_time) = declination_and_equation_of_time(unix_secs);
+    let utc_minutes = unix_secs.rem_euclid(SECS_PER_DAY) / 60.0;
+    let true_solar_time = (utc_minutes + eq_time + 4.0 * longitude).rem_euclid(1440.0);
+    let hour_angle = (true_solar_time / 4.0 - 180.0).to_radians();
+    let (lat, decl) = (latitude.to_radians(), declination.to_radians());
+    let cos_zenith = lat.sin() * decl.sin() + lat.cos() * decl.cos() * hour_angle.cos();
+    90.0 - cos_zenith.clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+/// 当地太阳日内太阳经过给定高度角的上午/下午时刻（Unix 秒），极昼极夜返回 None
+pub fn elevation_crossings(
+    unix_secs: f64,
+    latitude: f64,
+    longitude: f64,
+    elevation: f64,
+) -> Option<(f64, f64)> {
+    let day = ((unix_secs + longitude / 360.0 * SECS_PER_DAY) / SECS_PER_DAY).floor();
+    let approx_noon = day * SECS_PER_DAY + (720.0 - 4.0 * longitude) * 60.0;
+    let (declination, eq_time) = declination_and_equation_of_time(approx_noon);
+    let noon = day * SECS_PER_DAY + (720.0 - 4.0 * longitude - eq_time) * 60.0;
+    let (lat, decl) = (latitude.to_radians(), declination.to_radians());
+    let cos_hour_angle =
+        (elevation.to_radians().sin() - lat.sin() * decl.sin()) / (lat.cos() * decl.cos());
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    let offset = 4.0 * cos_hour_angle.acos().to_degrees() * 60.0;
+    Some((noon - offset, noon + offset))
+}
+
+pub fn civil_twilight(unix_secs: f64, latitude: f64, longitude: f64) -> Option<(f64, f64)> {
+    elevation_crossings(unix_secs, latitude, longitude, CIVIL_TWILIGHT_ELEVATION)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolarPhase {
+    pub color_temp: f64,
+    pub strength: f64,
+}
+
+impl SolarPhase {
+    /// 与预设、时间曲线关键帧的取值范围一致
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1000.0..=10000.0).contains(&self.color_temp) {
+            return Err("色温需在 1000K 到 10000K 之间".into());
+        }
+        if !(0.0..=100.0).contains(&self.strength) {
+            return Err("强度需在 0 到 100 之间".into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolarSchedule {
+    pub enabled: bool,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub day: SolarPhase,
+    pub night: SolarPhase,
+}
+
+impl Default for SolarSchedule {
+    fn default() -> Self {
+        // 默认位置北京，白天/夜间取「智能」预设
+        Self {
+            enabled: false,
+            latitude: 39.9,
+            longitude: 116.4,
+            day: SolarPhase {
+                color_temp: 4700.0,
+                strength: 30.0,
+            },
+            night: SolarPhase {
+                color_temp: 3400.0,
+                strength: 30.0,
+            },
+        }
+    }
+}
+
+impl SolarSchedule {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(-90.0..=90.0).contains(&self.latitude) {
+            return Err("纬度需在 -90 到 90 之间".into());
+        }
+        if !(-180.0..=180.0).contains(&self.longitude) {
+            return Err("经度需在 -180 到 180 之间".into());
+        }
+        self.day.validate().map_err(|err| format!("白天{}", err))?;
+        self.night.validate().map_err(|err| format!("夜间{}", err))
+    }
+
+    /// 0 为夜间，1 为白天，民用晨昏蒙影到日出后之间线性过渡
+    pub fn daylight(&self, unix_secs: f64) -> f64 {
+        let elevation = solar_elevation(unix_secs, self.latitude, self.longitude);
+        ((elevation - CIVIL_TWILIGHT_ELEVATION) / (DAY_ELEVATION - CIVIL_TWILIGHT_ELEVATION))
+            .clamp(0.0, 1.0)
+    }
+
+    /// 取整后的设置，避免每分钟都因细微变化触发写入
+    pub fn setting_at(&self, unix_secs: f64) -> FilterSetting {
+        let t = self.daylight(unix_secs);
+        let mix = |night: f64, day: f64| night + (day - night) * t;
+        FilterSetting {
+            filter_enabled: true,
+            strength: mix(self.night.strength, self.day.strength).round(),
+            color_temp: (mix(self.night.color_temp, self.day.color_temp) / 10.0).round() * 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolarStatus {
+    pub schedule: SolarSchedule,
+    pub elevation: f64,
+    pub dawn_ms: Option<i64>,
+    pub dusk_ms: Option<i64>,
+}
+
+#[derive(Default)]
+pub struct SolarState {
+    schedule: Mutex<SolarSchedule>,
+    /// 调度器最近一次写入的设置，用户手动调节后不会被立即覆盖
+    last_applied: Mutex<Option<FilterSetting>>,
+}
+
+impl SolarState {
+    pub fn schedule(&self) -> SolarSchedule {
+        self.schedule.lock().map(|value| *value).unwrap_or_default()
+    }
+
+    pub fn set_schedule(&self, schedule: SolarSchedule) -> Result<(), String> {
+        schedule.validate()?;
+        let mut current = self.schedule.lock().map_err(|_| "日照设置被占用")?;
+        *current = schedule;
+        if let Ok(mut last) = self.last_applied.lock() {
+            *last = None;
+        }
+        Ok(())
+    }
+
+    /// 时间曲线关闭或全局设置被替换后调用，下一次 tick 无论设置是否变化都重新写入
+    pub fn forget_applied(&self) {
+        if let Ok(mut last) = self.last_applied.lock() {
+            *last = None;
+        }
+    }
+
+    pub fn status(&self) -> SolarStatus {
+        let schedule = self.schedule();
+        let now = unix_now();
+        let twilight = civil_twilight(now, schedule.latitude, schedule.longitude);
+        SolarStatus {
+            schedule,
+            elevation: solar_elevation(now, schedule.latitude, schedule.longitude),
+            dawn_ms: twilight.map(|(dawn, _)| (dawn * 1000.0) as i64),
+            dusk_ms: twilight.map(|(_, dusk)| (dusk * 1000.0) as i64),
+        }
+    }
+
+    pub fn load(&self, path: &Path) {
+        let Ok(data) = fs::read_to_string(path) else {
+            return;
+        };
+        let Ok(saved) = serde_json::from_str::<SolarSchedule>(&data) else {
+            return;
+        };
+        let _ = self.set_schedule(saved);
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let data = serde_json::to_string_pretty(&self.schedule()).map_err(|err| err.to_string())?;
+        crate::settings::write_atomic(path, data.as_bytes())
+    }
+
+    /// 启用时返回需要写入的新设置，和上次相同则返回 None
+    fn next_setting(&self, unix_secs: f64) -> Option<FilterSetting> {
+        let schedule = self.schedule();
+        if !schedule.enabled {
+            return None;
+        }
+        let setting = schedule.setting_at(unix_secs);
+        let mut last = self.last_applied.lock().ok()?;
+        if *last == Some(setting) {
+            return None;
+        }
+        *last = Some(setting);
+        Some(setting)
+    }
+}
+
+fn unix_now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|value| value.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+/// 按当前太阳位置应用一次，并通知主界面同步滑块
+pub fn tick(app: &AppHandle) -> Result<(), String> {
+    // 自定义时间曲线优先
+    if app.state::<CurveState>().is_enabled() {
+        return Ok(());
+    }
+    let Some(setting) = app.state::<SolarState>().next_setting(unix_now()) else {
+        return Ok(());
+    };
+    crate::apply_global_filter(app, setting)?;
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit("solar-filter", setting);
+    }
+    Ok(())
+}
+
+/// 后台线程，主窗口隐藏到托盘时照常运行
+pub fn run_solar_schedule(app: AppHandle) {
+    loop {
+        if let Err(err) = tick(&app) {
+            crate::append_app_log(&app, &format!("日照色温应用失败: {}", err));
+        }
+        std::thread::sleep(Duration::from_secs(TICK_SECS));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-03-20 00:00 UTC（春分）与 2024-06-21 00:00 UTC（夏至）
+    const EQUINOX: f64 = 1710892800.0;
+    const SOLSTICE: f64 = 1718928000.0;
+
+    #[test]
+    fn equator_civil_day_is_about_twelve_point_eight_hours() {
+        let (dawn, dusk) = civil_twilight(EQUINOX + 6.0 * 3600.0, 0.0, 0.0).unwrap();
+        let minutes = (dusk - dawn) / 60.0;
+        assert!((minutes - 768.0).abs() < 5.0, "{}", minutes);
+        // 春分时差约 -7.5 分钟，正午在 12:07 UTC 左右
+        let noon_minutes = ((dawn + dusk) / 2.0 - EQUINOX) / 60.0;
+        assert!((noon_minutes - 727.5).abs() < 2.0, "{}", noon_minutes);
+    }
+
+    #[test]
+    fn noon_elevation_matches_declination() {
+        let (latitude, longitude) = (39.9, 116.4);
+        let (dawn, dusk) = civil_twilight(SOLSTICE, latitude, longitude).unwrap();
+        let noon = (dawn + dusk) / 2.0;
+        let elevation = solar_elevation(noon, latitude, longitude);
+        assert!(
+            (elevation - (90.0 - (latitude - 23.44))).abs() < 0.3,
+            "{}",
+            elevation
+        );
+        let at_dawn = solar_elevation(dawn, latitude, longitude);
+        assert!(
+            (at_dawn - CIVIL_TWILIGHT_ELEVATION).abs() < 0.2,
+            "{}",
+            at_dawn
+        );
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_phases() {
+        assert!(SolarSchedule::default().validate().is_ok());
+        let cold = SolarSchedule {
+            day: SolarPhase {
+                color_temp: 0.0,
+                strength: 30.0,
+            },
+            ..SolarSchedule::default()
+        };
+        assert!(cold.validate().unwrap_err().starts_with("白天色温"));
+        let strong = SolarSchedule {
+            night: SolarPhase {
+                color_temp: 3400.0,
+                strength: 500.0,
+            },
+            ..SolarSchedule::default()
+        };
+        assert!(strong.validate().unwrap_err().starts_with("夜间强度"));
+    }
+
+    #[test]
+    fn polar_day_has_no_twilight() {
+        assert!(civil_twilight(SOLSTICE, 78.2, 15.6).is_none());
+        assert!(civil_twilight(SOLSTICE, -78.2, 15.6).is_none());
+    }
+
+    #[test]
+    fn schedule_blends_between_night_and_day() {
+        let schedule = SolarSchedule {
+            enabled: true,
+            latitude: 0.0,
+            longitude: 0.0,
+            ..SolarSchedule::default()
+        };
+        let midnight = schedule.setting_at(EQUINOX);
+        assert_eq!(midnight.color_temp, schedule.night.color_temp);
+        let noon = schedule.setting_at(EQUINOX + 12.0 * 3600.0);
+        assert_eq!(noon.color_temp, schedule.day.color_temp);
+
+        let (dawn, _) = civil_twilight(EQUINOX, 0.0, 0.0).unwrap();
+        let twilight = schedule.setting_at(dawn + 10.0 * 60.0);
+        assert!(twilight.color_temp > schedule.night.color_temp);
+        assert!(twilight.color_temp < schedule.day.color_temp);
+    }
+
+    #[test]
+    fn state_skips_unchanged_settings() {
+        let state = SolarState::default();
+        assert!(state.next_setting(EQUINOX).is_none());
+        state
+            .set_schedule(SolarSchedule {
+                enabled: true,
+                ..SolarSchedule::default()
+            })
+            .unwrap();
+        assert!(state.next_setting(EQUINOX).is_some());
+        assert!(state.next_setting(EQUINOX + 60.0).is_none());
+        assert!(state
+            .set_schedule(SolarSchedule {
+                latitude: 120.0,
+                ..SolarSchedule::default()
+            })
+            .is_err());
+    }
+}
@@ -0,0 +1,199 @@
+// 严格休息模式的提前退出：应急口令只存 SHA-256，每日次数记在配置目录，跨天自动清零
+use crate::settings::{write_atomic, Settings, StrictSettings};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+pub const LEDGER_FILE: &str = "strict-overrides.json";
+
+/// 当天已用掉的提前结束次数
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OverrideLedger {
+    pub date: String,
+    pub used: u32,
+}
+
+impl OverrideLedger {
+    pub fn used_on(&self, date: &str) -> u32 {
+        if self.date == date {
+            self.used
+        } else {
+            0
+        }
+    }
+
+    fn record(&mut self, date: &str) {
+        if self.date != date {
+            self.date = date.to_string();
+            self.used = 0;
+        }
+        self.used += 1;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EarlyExit {
+    Passphrase,
+    Override { left: u32 },
+}
+
+impl EarlyExit {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Passphrase => "应急口令".to_string(),
+            Self::Override { left } => format!("今日次数（剩 {} 次）", left),
+        }
+    }
+}
+
+/// 给锁屏窗口展示的退出方式
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrictStatus {
+    pub enabled: bool,
+    pub has_passphrase: bool,
+    pub overrides_left: u32,
+}
+
+pub fn hash_passphrase(passphrase: &str) -> String {
+    Sha256::digest(format!("huyanba:{}", passphrase).as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+pub fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+/// 给了口令只比对口令；没给口令则消耗一次当日次数
+pub fn authorize(
+    strict: &StrictSettings,
+    ledger: &mut OverrideLedger,
+    today: &str,
+    passphrase: Option<&str>,
+) -> Result<EarlyExit, String> {
+    if let Some(passphrase) = passphrase.map(str::trim).filter(|value| !value.is_empty()) {
+        return match &strict.passphrase_hash {
+            Some(hash) if *hash == hash_passphrase(passphrase) => Ok(EarlyExit::Passphrase),
+            Some(_) => Err("应急口令不正确".into()),
+            None => Err("尚未设置应急口令".into()),
+        };
+    }
+    let used = ledger.used_on(today);
+    if used >= strict.daily_overrides {
+        return Err(format!(
+            "今天的 {} 次提前结束已经用完",
+            strict.daily_overrides
+        ));
+    }
+    ledger.record(today);
+    Ok(EarlyExit::Override {
+        left: strict.daily_overrides - used - 1,
+    })
+}
+
+#[derive(Default)]
+pub struct StrictState {
+    ledger: Mutex<OverrideLedger>,
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl StrictState {
+    /// 文件缺失或损坏都按当天未使用处理
+    pub fn load(&self, path: PathBuf) {
+        let ledger = fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        if let Ok(mut current) = self.ledger.lock() {
+            *current = ledger;
+        }
+        if let Ok(mut current) = self.path.lock() {
+            *current = Some(path);
+        }
+    }
+
+    pub fn status(&self, settings: &Settings) -> StrictStatus {
+        let strict = &settings.rest.strict;
+        let used = self
+            .ledger
+            .lock()
+            .map(|ledger| ledger.used_on(&today()))
+            .unwrap_or(0);
+        StrictStatus {
+            enabled: strict.enabled,
+            has_passphrase: strict.passphrase_hash.is_some(),
+            overrides_left: strict.daily_overrides.saturating_sub(used),
+        }
+    }
+
+    pub fn authorize(
+        &self,
+        settings: &Settings,
+        passphrase: Option<&str>,
+    ) -> Result<EarlyExit, String> {
+        let mut ledger = self.ledger.lock().map_err(|_| "严格模式状态被占用")?;
+        let exit = authorize(&settings.rest.strict, &mut ledger, &today(), passphrase)?;
+        if matches!(exit, EarlyExit::Override { .. }) {
+            let path = self.path.lock().ok().and_then(|value| value.clone());
+            if let Some(path) = path {
+                let data = serde_json::to_string_pretty(&*ledger).map_err(|err| err.to_string())?;
+                write_atomic(&path, data.as_bytes())?;
+            }
+        }
+        Ok(exit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strict() -> StrictSettings {
+        StrictSettings {
+            enabled: true,
+            passphrase_hash: Some(hash_passphrase("看远方")),
+            daily_overrides: 2,
+        }
+    }
+
+    #[test]
+    fn passphrase_is_checked_against_hash() {
+        let mut ledger = OverrideLedger::default();
+        assert_eq!(
+            authorize(&strict(), &mut ledger, "2026-10-17", Some(" 看远方 ")),
+            Ok(EarlyExit::Passphrase)
+        );
+        assert!(authorize(&strict(), &mut ledger, "2026-10-17", Some("错的")).is_err());
+        let no_hash = StrictSettings {
+            passphrase_hash: None,
+            ..strict()
+        };
+        assert!(authorize(&no_hash, &mut ledger, "2026-10-17", Some("看远方")).is_err());
+        // 口令不消耗次数
+        assert_eq!(ledger.used_on("2026-10-17"), 0);
+    }
+
+    #[test]
+    fn daily_overrides_run_out_and_reset_next_day() {
+        let mut ledger = OverrideLedger::default();
+        let day = "2026-10-17";
+        assert_eq!(
+            authorize(&strict(), &mut ledger, day, None),
+            Ok(EarlyExit::Override { left: 1 })
+        );
+        assert_eq!(
+            authorize(&strict(), &mut ledger, day, Some("")),
+            Ok(EarlyExit::Override { left: 0 })
+        );
+        assert!(authorize(&strict(), &mut ledger, day, None).is_err());
+        assert_eq!(
+            authorize(&strict(), &mut ledger, "2026-10-18", None),
+            Ok(EarlyExit::Override { left: 1 })
+        );
+    }
+}
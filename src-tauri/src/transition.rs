@@ -0,0 +1,186 @@
+// 色温渐变：后台线程按帧插值三通道倍率，新的请求到来时作废旧的渐变
+use crate::filter::FilterState;
+use crate::gamma::GammaState;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+type Multipliers = (f64, f64, f64);
+
+const NEUTRAL: Multipliers = (1.0, 1.0, 1.0);
+const FRAME_MS: u64 = 33;
+const DEFAULT_DURATION_MS: u64 = 800;
+const MAX_DURATION_MS: u64 = 10_000;
+
+pub struct TransitionState {
+    generation: AtomicU64,
+    duration_ms: AtomicU64,
+    /// 锁屏期间暂时撤掉滤镜，结束后再渐变回来
+    suspended: AtomicBool,
+    /// 最近一次实际写入的倍率，新渐变从这里出发
+    current: Mutex<HashMap<String, Multipliers>>,
+}
+
+impl Default for TransitionState {
+    fn default() -> Self {
+        Self {
+            generation: AtomicU64::new(0),
+            duration_ms: AtomicU64::new(DEFAULT_DURATION_MS),
+            suspended: AtomicBool::new(false),
+            current: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl TransitionState {
+    pub fn duration_ms(&self) -> u64 {
+        self.duration_ms.load(Ordering::SeqCst)
+    }
+
+    pub fn set_duration_ms(&self, duration_ms: u64) {
+        self.duration_ms
+            .store(duration_ms.min(MAX_DURATION_MS), Ordering::SeqCst);
+    }
+
+    pub fn set_suspended(&self, suspended: bool) {
+        self.suspended.store(suspended, Ordering::SeqCst);
+    }
+
+    /// 作废正在进行的渐变，返回新的代号；会等正在写入的那一帧写完，
+    /// 返回后旧渐变不会再写入任何一帧
+    pub fn cancel(&self) -> u64 {
+        let _frame = self.current.lock();
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// 还原曲线前调用：作废渐变并清空记录，下次渐变从中性出发
+    pub fn stop(&self) {
+        let mut current = self.current.lock().unwrap_or_else(|err| err.into_inner());
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        current.clear();
+    }
+
+    fn snapshot(&self) -> HashMap<String, Multipliers> {
+        self.current
+            .lock()
+            .map(|value| value.clone())
+            .unwrap_or_default()
+    }
+
+    /// 代号检查、写入和记录在同一把锁内完成，已作废的帧不会写入；作废时返回 None
+    fn write_frame(
+        &self,
+        gamma: &GammaState,
+        generation: u64,
+        frame: HashMap<String, Multipliers>,
+        multipliers: impl Fn(&str) -> Option<Multipliers>,
+    ) -> Option<Result<(), String>> {
+        let mut current = self.current.lock().unwrap_or_else(|err| err.into_inner());
+        if self.generation.load(Ordering::SeqCst) != generation {
+            return None;
+        }
+        let result = gamma.apply_with(multipliers);
+        *current = frame;
+        Some(result)
+    }
+}
+
+fn lerp(from: Multipliers, to: Multipliers, t: f64) -> Multipliers {
+    (
+        from.0 + (to.0 - from.0) * t,
+        from.1 + (to.1 - from.1) * t,
+        from.2 + (to.2 - from.2) * t,
+    )
+}
+
+fn ease_in_out(t: f64) -> f64 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// 按当前滤镜设置渐变到目标；`animate` 为 false 时立即写入
+pub fn apply_filter(app: &AppHandle, animate: bool) -> Result<(), String> {
+    let gamma = app.state::<GammaState>();
+    let filter = app.state::<FilterState>();
+    let transition = app.state::<TransitionState>();
+    let suspended = transition.suspended.load(Ordering::SeqCst);
+    let targets: HashMap<String, Option<Multipliers>> = gamma
+        .outputs()?
+        .into_iter()
+        .map(|output| {
+            let target = if suspended {
+                None
+            } else {
+                filter.multipliers_for(&output.id)
+            };
+            (output.id, target)
+        })
+        .collect();
+
+    let generation = transition.cancel();
+    let duration_ms = if animate { transition.duration_ms() } else { 0 };
+    if duration_ms < FRAME_MS {
+        let frame = final_frame(&targets);
+        return transition
+            .write_frame(&gamma, generation, frame, |output| {
+                targets.get(output).copied().flatten()
+            })
+            .unwrap_or(Ok(()));
+    }
+
+    let from = transition.snapshot();
+    let handle = app.clone();
+    std::thread::spawn(move || run_transition(handle, generation, from, targets, duration_ms));
+    Ok(())
+}
+
+fn final_frame(targets: &HashMap<String, Option<Multipliers>>) -> HashMap<String, Multipliers> {
+    targets
+        .iter()
+        .map(|(output, target)| (output.clone(), target.unwrap_or(NEUTRAL)))
+        .collect()
+}
+
+fn run_transition(
+    app: AppHandle,
+    generation: u64,
+    from: HashMap<String, Multipliers>,
+    targets: HashMap<String, Option<Multipliers>>,
+    duration_ms: u64,
+) {
+    let gamma = app.state::<GammaState>();
+    let transition = app.state::<TransitionState>();
+    let steps = (duration_ms / FRAME_MS).max(1);
+    for step in 1..=steps {
+        let result = if step == steps {
+            // 最后一帧写入精确目标，关闭滤镜的输出交给后端还原
+            transition.write_frame(&gamma, generation, final_frame(&targets), |output| {
+                targets.get(output).copied().flatten()
+            })
+        } else {
+            let t = ease_in_out(step as f64 / steps as f64);
+            let frame: HashMap<String, Multipliers> = targets
+                .iter()
+                .map(|(output, target)| {
+                    let start = from.get(output).copied().unwrap_or(NEUTRAL);
+                    (output.clone(), lerp(start, target.unwrap_or(NEUTRAL), t))
+                })
+                .collect();
+            let lookup = frame.clone();
+            transition.write_frame(&gamma, generation, frame, |output| {
+                lookup.get(output).copied()
+            })
+        };
+        let Some(result) = result else {
+            return;
+        };
+        if let Err(err) = result {
+            crate::append_app_log(&app, &format!("色温渐变失败: {}", err));
+            return;
+        }
+        if step < steps {
+            std::thread::sleep(Duration::from_millis(FRAME_MS));
+        }
+    }
+}
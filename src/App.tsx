@@ -1,4 +1,4 @@
-import { useCallback, useEffect, useMemo, useRef, useState } from "react";
+import { useCallback, useEffect, useState } from "react";
 import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { convertFileSrc, invoke } from "@tauri-apps/api/core";
 import "./App.css";
@@ -15,6 +15,11 @@ function formatDuration(totalSeconds: number) {
   return `${pad2(hours)}:${pad2(minutes)}:${pad2(seconds)}`;
 }
 
+function formatClock(timestampMs: number) {
+  const date = new Date(timestampMs);
+  return `${pad2(date.getHours())}:${pad2(date.getMinutes())}`;
+}
+
 function formatUsage(totalSeconds: number) {
   const clamped = Math.max(0, Math.floor(totalSeconds));
   const days = Math.floor(clamped / 86400);
@@ -29,6 +34,175 @@ function formatUsage(totalSeconds: number) {
   return `${minutes} 分钟`;
 }
 
+type ColorAlgorithm = "helland" | "planckian" | "redshiftTable";
+
+const colorAlgorithms: Array<{ value: ColorAlgorithm; label: string }> = [
+  { value: "helland", label: "柔和偏绿" },
+  { value: "planckian", label: "黑体精确" },
+  { value: "redshiftTable", label: "Redshift" },
+];
+
+type ToneCurve = {
+  brightness: number;
+  contrast: number;
+  gammaRed: number;
+  gammaGreen: number;
+  gammaBlue: number;
+};
+
+const gammaChannels: Array<{
+  key: "gammaRed" | "gammaGreen" | "gammaBlue";
+  label: string;
+}> = [
+  { key: "gammaRed", label: "红色 gamma" },
+  { key: "gammaGreen", label: "绿色 gamma" },
+  { key: "gammaBlue", label: "蓝色 gamma" },
+];
+
+type SolarSchedule = {
+  enabled: boolean;
+  latitude: number;
+  longitude: number;
+  day: { colorTemp: number; strength: number };
+  night: { colorTemp: number; strength: number };
+};
+
+type SolarStatus = {
+  schedule: SolarSchedule;
+  elevation: number;
+  dawnMs: number | null;
+  duskMs: number | null;
+};
+
+type Preset = {
+  name: string;
+  day: { colorTemp: number; strength: number };
+  night: { colorTemp: number; strength: number };
+  followSun: boolean;
+  rest: { workMinutes: number; restMinutes: number } | null;
+  builtin: boolean;
+};
+
+type CurveKeyframe = {
+  id: number;
+  time: string;
+  colorTemp: number;
+  strength: number;
+};
+
+type ColorCurve = {
+  enabled: boolean;
+  keyframes: CurveKeyframe[];
+};
+
+type OutputSetting = {
+  filterEnabled: boolean;
+  strength: number;
+  colorTemp: number;
+};
+
+type MicroBreakSettings = {
+  enabled: boolean;
+  intervalMinutes: number;
+  durationSeconds: number;
+};
+
+type StrictSettings = {
+  enabled: boolean;
+  passphraseHash: string | null;
+  dailyOverrides: number;
+};
+
+type StrictStatus = {
+  enabled: boolean;
+  hasPassphrase: boolean;
+  overridesLeft: number;
+};
+
+type LockAction =
+  | "exit"
+  | "togglePause"
+  | "nextWallpaper"
+  | "prevWallpaper"
+  | { snooze: number };
+
+type LockActionError = {
+  kind: "unknown" | "invalid" | "refused" | "failed";
+  message: string;
+};
+
+type LockUpdate = {
+  nowMs: number;
+  endAtMs: number | null;
+  remainingMs: number;
+  durationMs: number;
+  paused: boolean;
+  allowEscExit: boolean;
+  strict: boolean;
+  snoozesLeft: number;
+  snoozeMs: number;
+  wallpaper: string | null;
+};
+
+type Settings = {
+  version: number;
+  filter: OutputSetting;
+  rest: {
+    enabled: boolean;
+    workMinutes: number;
+    restMinutes: number;
+    idleMinutes: number;
+    preWarningSeconds: number;
+    maxSnoozes: number;
+    deferFullscreen: boolean;
+    deferApps: string[];
+    micro: MicroBreakSettings;
+    strict: StrictSettings;
+    lockOverscan: number;
+  };
+  allowEscExit: boolean;
+  transitionMs: number;
+  colorAlgorithm: ColorAlgorithm;
+  tone: ToneCurve;
+  activePreset: string | null;
+};
+
+type RestPhase =
+  | "disabled"
+  | "working"
+  | "preWarning"
+  | "resting"
+  | "paused"
+  | "snoozed"
+  | "deferred";
+
+type RestSnapshot = {
+  phase: RestPhase;
+  nextRestInMs: number | null;
+  restRemainingMs: number | null;
+  restDurationMs: number;
+  idle: boolean;
+  snoozeMs: number;
+  snoozesLeft: number;
+  deferredReason: string | null;
+  microPhase: "off" | "waiting" | "active";
+  nextMicroInMs: number | null;
+  microRemainingMs: number | null;
+  allowEscExit: boolean;
+  strict: boolean;
+};
+
+type DisplayOutput = {
+  id: string;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  scale: number;
+  gammaSupported: boolean;
+  custom: OutputSetting | null;
+};
+
 function App() {
   const isLockWindow =
     new URLSearchParams(window.location.search).get("lockscreen") === "1";
@@ -40,157 +214,408 @@ function App() {
   const [restEnabled, setRestEnabled] = useState(true);
   const [restMinutes, setRestMinutes] = useState(30);
   const [restDuration, setRestDuration] = useState(1);
+  const [idleMinutes, setIdleMinutes] = useState(5);
+  const [restIdle, setRestIdle] = useState(false);
+  const [preWarningSeconds, setPreWarningSeconds] = useState(60);
+  const [maxSnoozes, setMaxSnoozes] = useState(2);
+  const [lockOverscan, setLockOverscan] = useState(200);
+  const [snoozesLeft, setSnoozesLeft] = useState(2);
+  const [snoozeMinutes, setSnoozeMinutes] = useState(5);
+  const [deferFullscreen, setDeferFullscreen] = useState(true);
+  const [deferApps, setDeferApps] = useState("zoom, obs, powerpnt");
+  const [deferredReason, setDeferredReason] = useState<string | null>(null);
+  const [micro, setMicro] = useState<MicroBreakSettings>({
+    enabled: false,
+    intervalMinutes: 20,
+    durationSeconds: 20,
+  });
+  const [nextMicroAt, setNextMicroAt] = useState<number | null>(null);
   const [allowEscExit, setAllowEscExit] = useState(true);
+  const [strictEnabled, setStrictEnabled] = useState(false);
+  const [dailyOverrides, setDailyOverrides] = useState(2);
+  const [hasPassphrase, setHasPassphrase] = useState(false);
+  const [passphraseDraft, setPassphraseDraft] = useState("");
+  const [strictMessage, setStrictMessage] = useState<string | null>(null);
+  const [lockStrict, setLockStrict] = useState<StrictStatus | null>(null);
+  const [lockPassphrase, setLockPassphrase] = useState("");
+  const [lockExitError, setLockExitError] = useState<string | null>(null);
+  const [transitionMs, setTransitionMs] = useState(800);
+  const [tone, setTone] = useState<ToneCurve>({
+    brightness: 100,
+    contrast: 100,
+    gammaRed: 1,
+    gammaGreen: 1,
+    gammaBlue: 1,
+  });
+  const [colorAlgorithm, setColorAlgorithm] =
+    useState<ColorAlgorithm>("helland");
   const [showLockScreen, setShowLockScreen] = useState(false);
+  const [restPhase, setRestPhase] = useState<RestPhase>("working");
   const [activePreset, setActivePreset] = useState("智能");
   const [nextRestAt, setNextRestAt] = useState<Date | null>(null);
-  const [restEndAt, setRestEndAt] = useState<Date | null>(null);
-  const [restPaused, setRestPaused] = useState(false);
-  const [restPausedRemaining, setRestPausedRemaining] = useState<number | null>(
-    null,
-  );
-  const [lockPayload, setLockPayload] = useState({
-    timeText: "--:--",
-    dateText: "",
-    restCountdown: "00:00:00",
-    restPaused: false,
-    allowEscExit: true,
-  });
-  const [lockEndAtMs, setLockEndAtMs] = useState<number | null>(null);
-  const [lockPausedLocal, setLockPausedLocal] = useState(false);
-  const [lockRemainingLocal, setLockRemainingLocal] = useState(0);
-  const [lockBackgroundUrl, setLockBackgroundUrl] = useState<string | null>(
-    null,
-  );
-  const [lockWallpaperHistory, setLockWallpaperHistory] = useState<string[]>(
-    [],
-  );
-  const [lockWallpaperIndex, setLockWallpaperIndex] = useState(0);
-  const exitInProgressRef = useRef(false);
-  const exitRestRef = useRef<() => void>(() => {});
-  const togglePauseRef = useRef<() => void>(() => {});
-
-  const presets = useMemo(
-    () => ({
-      智能: {
-        day: { temp: 4700, strength: 30 },
-        night: { temp: 3400, strength: 30 },
-      },
-      办公: {
-        day: { temp: 5200, strength: 50 },
-        night: { temp: 4700, strength: 60 },
-      },
-      影视: {
-        day: { temp: 5600, strength: 45 },
-        night: { temp: 5200, strength: 55 },
-      },
-      游戏: {
-        day: { temp: 6000, strength: 35 },
-        night: { temp: 5600, strength: 45 },
-      },
-    }),
+  // 锁屏窗口的倒计时、暂停与壁纸都以后端推送为准
+  const [lockUpdate, setLockUpdate] = useState<LockUpdate | null>(null);
+  const [displayOutputs, setDisplayOutputs] = useState<DisplayOutput[]>([]);
+  const [solarLocation, setSolarLocation] = useState<{
+    latitude: number;
+    longitude: number;
+  } | null>(null);
+  const [solarStatus, setSolarStatus] = useState<SolarStatus | null>(null);
+  const [colorCurve, setColorCurve] = useState<ColorCurve | null>(null);
+  const [curveError, setCurveError] = useState<string | null>(null);
+  const [presets, setPresets] = useState<Preset[]>([]);
+  const [presetName, setPresetName] = useState("");
+  const [presetError, setPresetError] = useState<string | null>(null);
+  // 读取完已保存设置前不向后端回写，避免默认值覆盖
+  const [settingsLoaded, setSettingsLoaded] = useState(false);
+  const [profilePath, setProfilePath] = useState("");
+  const [profileMessage, setProfileMessage] = useState<string | null>(null);
+
+  const applySettings = useCallback((settings: Settings) => {
+    setFilterEnabled(settings.filter.filterEnabled);
+    setFilterStrength(settings.filter.strength);
+    setColorTemp(settings.filter.colorTemp);
+    setRestEnabled(settings.rest.enabled);
+    setRestMinutes(settings.rest.workMinutes);
+    setRestDuration(settings.rest.restMinutes);
+    setIdleMinutes(settings.rest.idleMinutes);
+    setPreWarningSeconds(settings.rest.preWarningSeconds);
+    setMaxSnoozes(settings.rest.maxSnoozes);
+    setLockOverscan(settings.rest.lockOverscan);
+    setDeferFullscreen(settings.rest.deferFullscreen);
+    setDeferApps(settings.rest.deferApps.join(", "));
+    setMicro(settings.rest.micro);
+    setAllowEscExit(settings.allowEscExit);
+    setStrictEnabled(settings.rest.strict.enabled);
+    setDailyOverrides(settings.rest.strict.dailyOverrides);
+    setHasPassphrase(settings.rest.strict.passphraseHash !== null);
+    setTransitionMs(settings.transitionMs);
+    setColorAlgorithm(settings.colorAlgorithm);
+    setTone({
+      ...settings.tone,
+      brightness: Math.round(settings.tone.brightness * 100),
+      contrast: Math.round(settings.tone.contrast * 100),
+    });
+    if (settings.activePreset) {
+      setActivePreset(settings.activePreset);
+    }
+  }, []);
+
+  useEffect(() => {
+    if (isLockWindow) return;
+    invoke<Settings>("get_settings")
+      .then(applySettings)
+      .catch((error) => console.error("读取设置失败", error))
+      .finally(() => setSettingsLoaded(true));
+  }, [isLockWindow, applySettings]);
+
+  useEffect(() => {
+    if (isLockWindow || !settingsLoaded) return;
+    const handle = setTimeout(() => {
+      invoke("update_settings", {
+        patch: {
+          rest: {
+            enabled: restEnabled,
+            workMinutes: restMinutes,
+            restMinutes: restDuration,
+            idleMinutes,
+            preWarningSeconds,
+            maxSnoozes,
+            deferFullscreen,
+            deferApps: deferApps.split(/[,，\s]+/).filter(Boolean),
+            micro,
+            strict: { enabled: strictEnabled, dailyOverrides },
+            lockOverscan,
+          },
+          allowEscExit,
+        },
+      }).catch((error) => console.error("保存设置失败", error));
+    }, 300);
+    return () => clearTimeout(handle);
+  }, [
+    isLockWindow,
+    settingsLoaded,
+    restEnabled,
+    restMinutes,
+    restDuration,
+    idleMinutes,
+    preWarningSeconds,
+    maxSnoozes,
+    deferFullscreen,
+    deferApps,
+    micro,
+    strictEnabled,
+    dailyOverrides,
+    lockOverscan,
+    allowEscExit,
+  ]);
+
+  // 口令只在设置或清除时单独提交，后端只保存摘要
+  const handleStrictPassphrase = useCallback((passphrase: string | null) => {
+    invoke<StrictStatus>("set_strict_passphrase", { passphrase })
+      .then((status) => {
+        setHasPassphrase(status.hasPassphrase);
+        setPassphraseDraft("");
+        setStrictMessage(
+          status.hasPassphrase ? "口令已保存" : "口令已清除",
+        );
+      })
+      .catch((error) => setStrictMessage(String(error)));
+  }, []);
+
+  useEffect(() => {
+    if (isLockWindow) return;
+    invoke<SolarStatus>("get_solar_schedule")
+      .then((status) => {
+        setSolarStatus(status);
+        setSolarLocation({
+          latitude: status.schedule.latitude,
+          longitude: status.schedule.longitude,
+        });
+      })
+      .catch((error) => console.error("读取日照设置失败", error));
+  }, [isLockWindow]);
+
+  useEffect(() => {
+    if (isLockWindow || !solarLocation) return;
+    const handle = setTimeout(() => {
+      invoke<SolarStatus>("set_solar_location", {
+        latitude: solarLocation.latitude,
+        longitude: solarLocation.longitude,
+      })
+        .then(setSolarStatus)
+        .catch((error) => console.error("日照设置失败", error));
+    }, 300);
+    return () => clearTimeout(handle);
+  }, [isLockWindow, solarLocation]);
+
+  const refreshSolarStatus = useCallback(() => {
+    invoke<SolarStatus>("get_solar_schedule")
+      .then(setSolarStatus)
+      .catch((error) => console.error("读取日照设置失败", error));
+  }, []);
+
+  const applyPreset = useCallback((name: string) => {
+    invoke("apply_preset", { name }).catch((error) =>
+      setPresetError(String(error)),
+    );
+  }, []);
+
+  const runPresetCommand = useCallback(
+    (command: string, args: Record<string, unknown>) => {
+      invoke<Preset[]>(command, args)
+        .then((list) => {
+          setPresets(list);
+          setPresetError(null);
+        })
+        .catch((error) => setPresetError(String(error)));
+    },
     [],
   );
 
-  const isDaytime = now.getHours() >= 6 && now.getHours() < 18;
-  const resolvePreset = useCallback(
-    (preset: keyof typeof presets) => {
-      const config = presets[preset];
-      if (!config) {
-        return { temp: 4700, strength: 30 };
+  useEffect(() => {
+    if (isLockWindow) return;
+    let unlisten: (() => void) | undefined;
+    getCurrentWebviewWindow()
+      .listen<Preset>("preset-applied", (event) => {
+        const preset = event.payload;
+        setActivePreset(preset.name);
+        setColorCurve((prev) => (prev ? { ...prev, enabled: false } : prev));
+        // 跟随日出日落的预设由 solar-filter 事件同步滑块
+        if (!preset.followSun) {
+          setFilterEnabled(true);
+          setFilterStrength(preset.day.strength);
+          setColorTemp(preset.day.colorTemp);
+        }
+        // 休息节奏已由后端保存并生效，这里只同步输入框
+        if (preset.rest) {
+          setRestMinutes(preset.rest.workMinutes);
+          setRestDuration(preset.rest.restMinutes);
+        }
+        refreshSolarStatus();
+      })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((error) => console.error("监听预设切换失败", error));
+
+    Promise.all([
+      invoke<Preset[]>("list_presets"),
+      invoke<string | null>("get_active_preset"),
+      invoke<ColorCurve>("get_color_curve"),
+    ])
+      .then(([list, active, curve]) => {
+        setPresets(list);
+        setColorCurve(curve);
+        if (curve.enabled) {
+          setActivePreset("时间曲线");
+        } else if (active) {
+          setActivePreset(active);
+        } else {
+          applyPreset("智能");
+        }
+      })
+      .catch((error) => console.error("读取预设失败", error));
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [isLockWindow, applyPreset, refreshSolarStatus]);
+
+  useEffect(() => {
+    if (isLockWindow) return;
+    let unlisten: (() => void) | undefined;
+    getCurrentWebviewWindow()
+      .listen<OutputSetting>("solar-filter", (event) => {
+        setFilterEnabled(event.payload.filterEnabled);
+        setFilterStrength(event.payload.strength);
+        setColorTemp(event.payload.colorTemp);
+      })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((error) => console.error("监听日照色温失败", error));
+    return () => {
+      if (unlisten) {
+        unlisten();
       }
-      if (preset === "智能") {
-        return isDaytime ? config.day : config.night;
+    };
+  }, [isLockWindow]);
+
+  useEffect(() => {
+    if (isLockWindow) return;
+    let unlisten: (() => void) | undefined;
+    getCurrentWebviewWindow()
+      .listen<OutputSetting>("curve-filter", (event) => {
+        setFilterEnabled(event.payload.filterEnabled);
+        setFilterStrength(event.payload.strength);
+        setColorTemp(event.payload.colorTemp);
+      })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((error) => console.error("监听时间曲线失败", error));
+    return () => {
+      if (unlisten) {
+        unlisten();
       }
-      return config.day;
+    };
+  }, [isLockWindow]);
+
+  // 以当前滑块值作为白天/夜间设置，并附带当前休息节奏
+  const currentPreset = (name: string): Preset => ({
+    name,
+    day: { colorTemp, strength: filterStrength },
+    night: { colorTemp, strength: filterStrength },
+    followSun: false,
+    rest: { workMinutes: restMinutes, restMinutes: restDuration },
+    builtin: false,
+  });
+
+  const runCurveCommand = useCallback(
+    (command: string, args: Record<string, unknown>) => {
+      invoke<ColorCurve>(command, args)
+        .then((curve) => {
+          setColorCurve(curve);
+          setCurveError(null);
+        })
+        .catch((error) => setCurveError(String(error)));
     },
-    [isDaytime, presets],
+    [],
   );
 
-  useEffect(() => {
-    if (activePreset !== "智能") return;
-    const next = resolvePreset("智能");
-    setFilterStrength(next.strength);
-    setColorTemp(next.temp);
-  }, [activePreset, resolvePreset]);
-
+  // 休息节奏由后端调度，这里只发指令并按 rest-state 事件同步界面
   const handleStartRest = useCallback(() => {
-    exitInProgressRef.current = false;
-    const endAt = new Date(Date.now() + restDuration * 60 * 1000);
-    setRestPaused(false);
-    setRestPausedRemaining(null);
-    setRestEndAt(endAt);
-    setShowLockScreen(true);
-  }, [restDuration]);
+    invoke("start_rest").catch((error) =>
+      console.error("进入休息失败", error),
+    );
+  }, []);
 
   const handleExitRest = useCallback(() => {
-    if (exitInProgressRef.current) return;
-    exitInProgressRef.current = true;
-    invoke("log_app", { message: "前端退出休息: start" }).catch(() => undefined);
-    setShowLockScreen(false);
-    setRestPaused(false);
-    setRestPausedRemaining(null);
-    setRestEndAt(null);
-    if (restEnabled) {
-      setNextRestAt(new Date(Date.now() + restMinutes * 60 * 1000));
-    } else {
-      setNextRestAt(null);
-    }
-    if (!isLockWindow) {
-      invoke("set_gamma", {
-        filterEnabled,
-        strength: filterStrength,
-        colorTemp,
-      }).catch(() => undefined);
-    }
-    invoke("log_app", { message: "前端退出休息: end" }).catch(() => undefined);
-  }, [
-    restEnabled,
-    restMinutes,
-    isLockWindow,
-    filterEnabled,
-    filterStrength,
-    colorTemp,
-  ]);
+    invoke("end_rest").catch((error) => console.error("退出休息失败", error));
+  }, []);
 
-  const handleTogglePause = useCallback(() => {
-    if (!showLockScreen) return;
-    if (restPaused) {
-      if (restPausedRemaining === null) return;
-      setRestEndAt(new Date(Date.now() + restPausedRemaining * 1000));
-      setRestPaused(false);
-      setRestPausedRemaining(null);
-      return;
-    }
-    if (!restEndAt) return;
-    const remaining = Math.max(
-      0,
-      Math.floor((restEndAt.getTime() - Date.now()) / 1000),
+  const handleSnoozeRest = useCallback(() => {
+    invoke("snooze_rest").catch((error) =>
+      console.error("推迟休息失败", error),
+    );
+  }, []);
+
+  const handleSkipRest = useCallback(() => {
+    invoke("skip_rest").catch((error) =>
+      console.error("跳过休息失败", error),
     );
-    setRestPausedRemaining(remaining);
-    setRestEndAt(null);
-    setRestPaused(true);
-  }, [restEndAt, restPaused, restPausedRemaining, showLockScreen]);
+  }, []);
 
+  // 严格模式下锁屏窗口读取剩余次数和是否设置了口令
   useEffect(() => {
-    exitRestRef.current = handleExitRest;
-  }, [handleExitRest]);
+    if (!isLockWindow) return;
+    invoke<StrictStatus>("get_strict_status")
+      .then(setLockStrict)
+      .catch((error) => console.error("读取严格模式失败", error));
+  }, [isLockWindow]);
+
+  const handleEmergencyExit = useCallback((passphrase: string | null) => {
+    invoke("emergency_exit", { passphrase })
+      .then(() => setLockExitError(null))
+      .catch((error) => {
+        setLockExitError(String(error));
+        return invoke<StrictStatus>("get_strict_status").then(setLockStrict);
+      });
+  }, []);
+
+  const applyRestSnapshot = useCallback((snapshot: RestSnapshot) => {
+    const nowMs = Date.now();
+    setRestPhase(snapshot.phase);
+    setRestIdle(snapshot.idle);
+    setSnoozesLeft(snapshot.snoozesLeft);
+    setDeferredReason(snapshot.deferredReason);
+    setNextMicroAt(
+      snapshot.nextMicroInMs !== null ? nowMs + snapshot.nextMicroInMs : null,
+    );
+    setSnoozeMinutes(Math.ceil(snapshot.snoozeMs / 60000));
+    setNextRestAt(
+      snapshot.nextRestInMs !== null
+        ? new Date(nowMs + snapshot.nextRestInMs)
+        : null,
+    );
+    setShowLockScreen(
+      snapshot.phase === "resting" || snapshot.phase === "paused",
+    );
+  }, []);
 
   useEffect(() => {
-    togglePauseRef.current = handleTogglePause;
-  }, [handleTogglePause]);
+    let unlisten: (() => void) | undefined;
+    getCurrentWebviewWindow()
+      .listen<RestSnapshot>("rest-state", (event) => {
+        // 锁屏窗口改听 lockscreen-update
+        if (!isLockWindow) {
+          applyRestSnapshot(event.payload);
+        }
+      })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((error) => console.error("监听休息状态失败", error));
+    if (!isLockWindow) {
+      invoke<RestSnapshot>("get_rest_state")
+        .then(applyRestSnapshot)
+        .catch((error) => console.error("读取休息状态失败", error));
+    }
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [isLockWindow, applyRestSnapshot]);
 
   useEffect(() => {
     const timer = setInterval(() => setNow(new Date()), 1000);
     return () => clearInterval(timer);
   }, []);
 
-  useEffect(() => {
-    if (showLockScreen) {
-      exitInProgressRef.current = false;
-    }
-  }, [showLockScreen]);
-
   useEffect(() => {
     if (isLockWindow) return;
     const reset = () => {
@@ -204,7 +629,7 @@ function App() {
   }, [isLockWindow]);
 
   useEffect(() => {
-    if (isLockWindow) return;
+    if (isLockWindow || !settingsLoaded) return;
     let active = true;
     const handle = setTimeout(() => {
       invoke("set_gamma", {
@@ -221,7 +646,98 @@ function App() {
       active = false;
       clearTimeout(handle);
     };
-  }, [isLockWindow, filterEnabled, filterStrength, colorTemp]);
+  }, [
+    isLockWindow,
+    settingsLoaded,
+    filterEnabled,
+    filterStrength,
+    colorTemp,
+  ]);
+
+  useEffect(() => {
+    if (isLockWindow || !settingsLoaded) return;
+    let active = true;
+    const handle = setTimeout(() => {
+      invoke("set_tone_curve", {
+        brightness: tone.brightness / 100,
+        contrast: tone.contrast / 100,
+        gammaRed: tone.gammaRed,
+        gammaGreen: tone.gammaGreen,
+        gammaBlue: tone.gammaBlue,
+      }).catch((error) => {
+        if (active) {
+          console.error("亮度调节失败", error);
+        }
+      });
+    }, 80);
+    return () => {
+      active = false;
+      clearTimeout(handle);
+    };
+  }, [isLockWindow, settingsLoaded, tone]);
+
+  useEffect(() => {
+    if (isLockWindow || !settingsLoaded) return;
+    invoke("set_transition_duration", { durationMs: transitionMs }).catch(
+      (error) => console.error("渐变时长设置失败", error),
+    );
+  }, [isLockWindow, settingsLoaded, transitionMs]);
+
+  useEffect(() => {
+    if (isLockWindow || !settingsLoaded) return;
+    invoke("set_color_algorithm", { algorithm: colorAlgorithm }).catch(
+      (error) => console.error("色温算法设置失败", error),
+    );
+  }, [isLockWindow, settingsLoaded, colorAlgorithm]);
+
+  const refreshDisplayOutputs = useCallback(() => {
+    invoke<DisplayOutput[]>("list_display_outputs")
+      .then(setDisplayOutputs)
+      .catch((error) => console.error("读取显示器列表失败", error));
+  }, []);
+
+  useEffect(() => {
+    if (isLockWindow) return;
+    refreshDisplayOutputs();
+    const timer = window.setInterval(refreshDisplayOutputs, 5000);
+    return () => window.clearInterval(timer);
+  }, [isLockWindow, refreshDisplayOutputs]);
+
+  const updateOutputSetting = useCallback(
+    (outputId: string, custom: OutputSetting | null) => {
+      setDisplayOutputs((prev) =>
+        prev.map((item) => (item.id === outputId ? { ...item, custom } : item)),
+      );
+      const request = custom
+        ? invoke("set_output_gamma", { outputId, ...custom })
+        : invoke("clear_output_gamma", { outputId });
+      request.catch((error) => console.error("显示器色温设置失败", error));
+    },
+    [],
+  );
+
+  // 导入/恢复默认后后端已保存并生效，这里只同步界面
+  const runProfileCommand = useCallback(
+    (command: string, args: Record<string, unknown>) => {
+      invoke<Settings | null>(command, args)
+        .then((settings) => {
+          if (settings) {
+            applySettings(settings);
+          }
+          setProfileMessage(
+            command === "export_settings" ? "已导出" : "已应用",
+          );
+          refreshSolarStatus();
+          refreshDisplayOutputs();
+          return Promise.all([
+            invoke<Preset[]>("list_presets").then(setPresets),
+            invoke<ColorCurve>("get_color_curve").then(setColorCurve),
+          ]);
+        })
+        .catch((error) => setProfileMessage(String(error)));
+    },
+    [applySettings, refreshSolarStatus, refreshDisplayOutputs],
+  );
 
   useEffect(() => {
     if (isLockWindow) return;
@@ -242,244 +758,63 @@ function App() {
   }, [isLockWindow]);
 
   useEffect(() => {
-    if (isLockWindow) return;
-    if (showLockScreen) {
-      const endAt = restEndAt ?? new Date(Date.now() + restDuration * 60 * 1000);
-      invoke("show_lock_windows", {
-        endAtMs: endAt.getTime(),
-        paused: restPaused,
-        pausedRemaining: restPausedRemaining || 0,
-        allowEsc: allowEscExit,
-      }).catch((error) => console.error("锁屏窗口创建失败", error));
-    } else {
-      invoke("log_app", { message: "前端请求关闭锁屏" }).catch(() => undefined);
-      invoke("hide_lock_windows").catch((error) =>
-        console.error("锁屏窗口关闭失败", error),
-      );
-    }
-  }, [
-    isLockWindow,
-    showLockScreen,
-    restEndAt,
-    restDuration,
-    restPaused,
-    restPausedRemaining,
-    allowEscExit,
-  ]);
-
-  useEffect(() => {
-    if (isLockWindow) return;
+    if (!isLockWindow) return;
     let unlisten: (() => void) | undefined;
-    const window = getCurrentWebviewWindow();
-    window
-      .listen<string>("lockscreen-action", (event) => {
-        if (event.payload === "exit") {
-          exitRestRef.current();
-        } else if (event.payload === "toggle_pause") {
-          togglePauseRef.current();
-        }
-      })
+    getCurrentWebviewWindow()
+      .listen<LockUpdate>("lockscreen-update", (event) =>
+        setLockUpdate(event.payload),
+      )
       .then((fn) => {
         unlisten = fn;
       })
-      .catch((error) => console.error("监听锁屏动作失败", error));
-
-    return () => {
-      if (unlisten) {
-        unlisten();
-      }
-    };
-  }, [isLockWindow]);
-
-  useEffect(() => {
-    if (!isLockWindow) return;
-    const params = new URLSearchParams(window.location.search);
-    const end = Number(params.get("end") || 0);
-    const paused = params.get("paused") === "1";
-    const remaining = Number(params.get("remaining") || 0);
-    const allowEsc = params.get("allowEsc") !== "0";
-    setLockEndAtMs(end > 0 ? end : null);
-    setLockPausedLocal(paused);
-    setLockRemainingLocal(remaining);
-    setLockPayload((prev) => ({
-      ...prev,
-      allowEscExit: allowEsc,
-    }));
-  }, [isLockWindow]);
-
-  useEffect(() => {
-    if (!isLockWindow) return;
-    let active = true;
-    invoke<string | null>("get_lock_wallpaper")
-      .then((path) => {
-        if (!active) return;
-        if (path) {
-          const url = convertFileSrc(path);
-          setLockBackgroundUrl(url);
-          setLockWallpaperHistory([url]);
-          setLockWallpaperIndex(0);
-        } else {
-          setLockBackgroundUrl(null);
-          setLockWallpaperHistory([]);
-          setLockWallpaperIndex(0);
+      .catch((error) => console.error("监听锁屏状态失败", error));
+    invoke<LockUpdate | null>("get_lock_update")
+      .then((update) => {
+        if (update) {
+          setLockUpdate(update);
         }
       })
-      .catch((error) => {
-        console.error("获取锁屏壁纸失败", error);
-        setLockBackgroundUrl(null);
-        setLockWallpaperHistory([]);
-        setLockWallpaperIndex(0);
-      });
-    return () => {
-      active = false;
-    };
+      .catch((error) => console.error("读取锁屏状态失败", error));
+    return () => unlisten?.();
   }, [isLockWindow]);
 
-  const handleNextWallpaper = useCallback(() => {
-    if (!isLockWindow) return;
-    if (lockWallpaperIndex < lockWallpaperHistory.length - 1) {
-      const nextIndex = lockWallpaperIndex + 1;
-      setLockWallpaperIndex(nextIndex);
-      setLockBackgroundUrl(lockWallpaperHistory[nextIndex]);
-      return;
-    }
-    invoke<string | null>("get_lock_wallpaper")
-      .then((path) => {
-        if (!path) return;
-        const url = convertFileSrc(path);
-        setLockWallpaperHistory((prev) => [...prev, url]);
-        setLockWallpaperIndex((prev) => prev + 1);
-        setLockBackgroundUrl(url);
-      })
-      .catch((error) => console.error("切换壁纸失败", error));
-  }, [isLockWindow, lockWallpaperHistory, lockWallpaperIndex]);
-
-  const handlePrevWallpaper = useCallback(() => {
-    if (!isLockWindow) return;
-    if (lockWallpaperIndex <= 0) return;
-    const nextIndex = lockWallpaperIndex - 1;
-    setLockWallpaperIndex(nextIndex);
-    setLockBackgroundUrl(lockWallpaperHistory[nextIndex]);
-  }, [isLockWindow, lockWallpaperHistory, lockWallpaperIndex]);
-
-  useEffect(() => {
-    if (!isLockWindow) return;
-    const timer = setInterval(() => {
-      const nowValue = new Date();
-      const timeValue = nowValue.toLocaleTimeString("zh-CN", {
-        hour: "2-digit",
-        minute: "2-digit",
-      });
-      const dateValue = nowValue.toLocaleDateString("zh-CN", {
-        month: "long",
-        day: "numeric",
-        weekday: "short",
-      });
-
-      let countdown = "00:00:00";
-      if (lockPausedLocal) {
-        countdown = formatDuration(lockRemainingLocal);
-      } else if (lockEndAtMs) {
-        countdown = formatDuration((lockEndAtMs - nowValue.getTime()) / 1000);
-      }
-
-      setLockPayload((prev) => ({
-        ...prev,
-        timeText: timeValue,
-        dateText: dateValue,
-        restCountdown: countdown,
-        restPaused: lockPausedLocal,
-      }));
-    }, 500);
-    return () => clearInterval(timer);
-  }, [isLockWindow, lockEndAtMs, lockPausedLocal, lockRemainingLocal]);
+  // 锁屏动作都交给后端校验，壁纸和倒计时的变化随 lockscreen-update 回来
+  const runLockAction = useCallback((action: LockAction) => {
+    invoke("lockscreen_action", { action })
+      .then(() => setLockExitError(null))
+      .catch((error: LockActionError) => setLockExitError(error.message));
+  }, []);
 
   useEffect(() => {
     if (!isLockWindow) return;
     function onKeydown(event: KeyboardEvent) {
-      if (!lockPayload.allowEscExit) return;
+      if (!lockUpdate?.allowEscExit) return;
       if (event.key === "Escape") {
-        invoke("lockscreen_action", { action: "exit" }).catch((error) =>
-          console.error("锁屏退出失败", error),
-        );
+        runLockAction("exit");
       }
     }
     window.addEventListener("keydown", onKeydown);
     return () => window.removeEventListener("keydown", onKeydown);
-  }, [isLockWindow, lockPayload.allowEscExit]);
+  }, [isLockWindow, lockUpdate?.allowEscExit, runLockAction]);
 
   // 全局快捷键已取消
 
-  useEffect(() => {
-    if (showLockScreen) return;
-    if (!restEnabled) {
-      setNextRestAt(null);
-      return;
-    }
-    const next = new Date(Date.now() + restMinutes * 60 * 1000);
-    setNextRestAt(next);
-  }, [showLockScreen, restEnabled, restMinutes]);
-
-  useEffect(() => {
-    if (!restEnabled || showLockScreen) return;
-    if (!nextRestAt) return;
-    if (now.getTime() >= nextRestAt.getTime()) {
-      const endAt = new Date(Date.now() + restDuration * 60 * 1000);
-      setRestPaused(false);
-      setRestPausedRemaining(null);
-      setRestEndAt(endAt);
-      setShowLockScreen(true);
-    }
-  }, [now, restEnabled, nextRestAt, restDuration, showLockScreen]);
-
-  useEffect(() => {
-    if (!showLockScreen || !restEndAt) return;
-    if (restPaused) return;
-    if (now.getTime() >= restEndAt.getTime()) {
-      handleExitRest();
-    }
-  }, [handleExitRest, now, restPaused, restEndAt, showLockScreen]);
-
-  useEffect(() => {
-    if (!showLockScreen) return;
-    if (restPaused) {
-      setRestPausedRemaining(restDuration * 60);
-      return;
-    }
-    setRestEndAt(new Date(Date.now() + restDuration * 60 * 1000));
-  }, [restDuration, showLockScreen, restPaused]);
-
   useEffect(() => {
     if (!showLockScreen) return;
     function onKeydown(event: KeyboardEvent) {
-      if (!allowEscExit) return;
+      if (!allowEscExit || strictEnabled) return;
       if (event.key === "Escape") {
         handleExitRest();
       }
     }
     window.addEventListener("keydown", onKeydown);
     return () => window.removeEventListener("keydown", onKeydown);
-  }, [showLockScreen, allowEscExit, handleExitRest]);
+  }, [showLockScreen, allowEscExit, strictEnabled, handleExitRest]);
 
-  useEffect(() => {
-    if (showLockScreen) return;
-    if (!restEnabled || !nextRestAt) return;
-    if (now.getTime() < nextRestAt.getTime()) return;
-    setNextRestAt(new Date(Date.now() + restMinutes * 60 * 1000));
-  }, [now, showLockScreen, restEnabled, nextRestAt, restMinutes]);
-
-  const nextRestCountdown = restEnabled && nextRestAt
+  const nextRestCountdown = nextRestAt
     ? formatDuration((nextRestAt.getTime() - now.getTime()) / 1000)
     : "已暂停";
 
-  const restCountdownSeconds =
-    showLockScreen && restPaused && restPausedRemaining !== null
-      ? restPausedRemaining
-      : showLockScreen && restEndAt
-        ? (restEndAt.getTime() - now.getTime()) / 1000
-        : restDuration * 60;
-  const restCountdown = formatDuration(restCountdownSeconds);
-
   const timeText = now.toLocaleTimeString("zh-CN", {
     hour: "2-digit",
     minute: "2-digit",
@@ -491,49 +826,23 @@ function App() {
   });
   const usageText = formatUsage((now.getTime() - sessionStart) / 1000);
 
-  useEffect(() => {
-    if (isLockWindow) return;
-    if (!showLockScreen) return;
-    invoke("broadcast_lock_update", {
-      timeText,
-      dateText,
-      restCountdown,
-      restPaused,
-      allowEscExit,
-    }).catch((error) => console.error("锁屏数据同步失败", error));
-  }, [
-    isLockWindow,
-    showLockScreen,
-    timeText,
-    dateText,
-    restCountdown,
-    restPaused,
-    allowEscExit,
-  ]);
-
-
-  useEffect(() => {
-    if (isLockWindow) return;
-    if (!showLockScreen) return;
-    const timer = setInterval(() => {
-      invoke("broadcast_lock_update", {
-        timeText,
-        dateText,
-        restCountdown,
-        restPaused,
-        allowEscExit,
-      }).catch((error) => console.error("锁屏数据同步失败", error));
-    }, 1000);
-    return () => clearInterval(timer);
-  }, [
-    isLockWindow,
-    showLockScreen,
-    timeText,
-    dateText,
-    restCountdown,
-    restPaused,
-    allowEscExit,
-  ]);
+  const lockNow = new Date(lockUpdate?.nowMs ?? now.getTime());
+  const lockTimeText = lockNow.toLocaleTimeString("zh-CN", {
+    hour: "2-digit",
+    minute: "2-digit",
+  });
+  const lockDateText = lockNow.toLocaleDateString("zh-CN", {
+    month: "long",
+    day: "numeric",
+    weekday: "short",
+  });
+  const lockPaused = lockUpdate?.paused ?? false;
+  const lockCountdown = formatDuration((lockUpdate?.remainingMs ?? 0) / 1000);
+  const lockSnoozesLeft = lockUpdate?.snoozesLeft ?? 0;
+  const lockSnoozeMs = lockUpdate?.snoozeMs ?? 0;
+  const lockBackgroundUrl = lockUpdate?.wallpaper
+    ? convertFileSrc(lockUpdate.wallpaper)
+    : null;
 
   return (
     <div className="app">
@@ -624,28 +933,82 @@ function App() {
           </div>
 
           <div className="chips">
-            {(Object.keys(presets) as Array<keyof typeof presets>).map(
-              (preset) => (
-                <button
-                  key={preset}
-                  type="button"
-                  className={`chip ${
-                    activePreset === preset ? "chip--active" : ""
-                  }`}
-                  onClick={() => {
-                    setActivePreset(preset);
-                    const next = resolvePreset(preset);
-                    setFilterStrength(next.strength);
-                    setColorTemp(next.temp);
-                    setFilterEnabled(true);
-                  }}
-                >
-                  {preset}
-                </button>
-              ),
-            )}
+            {presets.map((preset) => (
+              <button
+                key={preset.name}
+                type="button"
+                className={`chip ${
+                  activePreset === preset.name ? "chip--active" : ""
+                }`}
+                onClick={() => applyPreset(preset.name)}
+              >
+                {preset.name}
+              </button>
+            ))}
+          </div>
+
+          <div className="setting-row">
+            <input
+              className="text-input"
+              type="text"
+              placeholder="新预设名称"
+              value={presetName}
+              onChange={(event) => setPresetName(event.target.value)}
+            />
+            <button
+              className="btn btn--soft"
+              type="button"
+              onClick={() => {
+                runPresetCommand("create_preset", {
+                  preset: currentPreset(presetName),
+                });
+                setPresetName("");
+              }}
+            >
+              保存为预设
+            </button>
           </div>
 
+          {presets.some(
+            (preset) => preset.name === activePreset && !preset.builtin,
+          ) && (
+            <div className="setting-row">
+              <button
+                className="btn btn--ghost"
+                type="button"
+                onClick={() =>
+                  runPresetCommand("update_preset", {
+                    name: activePreset,
+                    preset: currentPreset(activePreset),
+                  })
+                }
+              >
+                用当前设置覆盖
+              </button>
+              <button
+                className="btn btn--ghost"
+                type="button"
+                onClick={() =>
+                  runPresetCommand("delete_preset", { name: activePreset })
+                }
+              >
+                删除预设
+              </button>
+            </div>
+          )}
+
+          {presetError && <p className="helper-text">{presetError}</p>}
+
+          {activePreset === "智能" && solarStatus && (
+            <p className="helper-text">
+              {solarStatus.dawnMs !== null && solarStatus.duskMs !== null
+                ? `按日出日落自动调节：晨光 ${formatClock(
+                    solarStatus.dawnMs,
+                  )} · 暮光 ${formatClock(solarStatus.duskMs)}`
+                : "当前位置处于极昼或极夜，按太阳高度自动调节"}
+            </p>
+          )}
+
           <div className="slider-group">
             <div className="slider-row">
               <span>色调</span>
@@ -660,6 +1023,66 @@ function App() {
               onChange={(event) => setColorTemp(Number(event.target.value))}
             />
           </div>
+
+          <div className="slider-group">
+            <div className="slider-row">
+              <span>亮度</span>
+              <span>{tone.brightness}%</span>
+            </div>
+            <input
+              type="range"
+              min={10}
+              max={100}
+              value={tone.brightness}
+              onChange={(event) =>
+                setTone((prev) => ({
+                  ...prev,
+                  brightness: Number(event.target.value),
+                }))
+              }
+            />
+          </div>
+
+          <div className="slider-group">
+            <div className="slider-row">
+              <span>对比度</span>
+              <span>{tone.contrast}%</span>
+            </div>
+            <input
+              type="range"
+              min={50}
+              max={150}
+              value={tone.contrast}
+              onChange={(event) =>
+                setTone((prev) => ({
+                  ...prev,
+                  contrast: Number(event.target.value),
+                }))
+              }
+            />
+          </div>
+
+          {gammaChannels.map((channel) => (
+            <div className="slider-group" key={channel.key}>
+              <div className="slider-row">
+                <span>{channel.label}</span>
+                <span>{tone[channel.key].toFixed(2)}</span>
+              </div>
+              <input
+                type="range"
+                min={0.5}
+                max={2.5}
+                step={0.05}
+                value={tone[channel.key]}
+                onChange={(event) =>
+                  setTone((prev) => ({
+                    ...prev,
+                    [channel.key]: Number(event.target.value),
+                  }))
+                }
+              />
+            </div>
+          ))}
         </div>
 
         <div className="card">
@@ -703,20 +1126,122 @@ function App() {
               />
               <span>分钟</span>
             </div>
+            <div className="pill">
+              <p className="pill__label">离开</p>
+              <input
+                className="pill__input"
+                type="number"
+                min={0}
+                max={120}
+                value={idleMinutes}
+                onChange={(event) => setIdleMinutes(Number(event.target.value))}
+              />
+              <span>分钟算休息</span>
+            </div>
           </div>
 
+          <label className="setting-row">
+            <span>
+              20-20-20 小憩
+              {micro.enabled && nextMicroAt !== null
+                ? `（${formatClock(nextMicroAt)}）`
+                : ""}
+            </span>
+            <label className="toggle">
+              <input
+                type="checkbox"
+                checked={micro.enabled}
+                onChange={() =>
+                  setMicro((prev) => ({ ...prev, enabled: !prev.enabled }))
+                }
+              />
+              <span className="toggle__track" />
+            </label>
+          </label>
+
+          {micro.enabled && (
+            <div className="pill-row">
+              <div className="pill">
+                <p className="pill__label">每隔</p>
+                <input
+                  className="pill__input"
+                  type="number"
+                  min={5}
+                  max={120}
+                  value={micro.intervalMinutes}
+                  onChange={(event) =>
+                    setMicro((prev) => ({
+                      ...prev,
+                      intervalMinutes: Number(event.target.value),
+                    }))
+                  }
+                />
+                <span>分钟</span>
+              </div>
+              <div className="pill">
+                <p className="pill__label">看远处</p>
+                <input
+                  className="pill__input"
+                  type="number"
+                  min={10}
+                  max={120}
+                  value={micro.durationSeconds}
+                  onChange={(event) =>
+                    setMicro((prev) => ({
+                      ...prev,
+                      durationSeconds: Number(event.target.value),
+                    }))
+                  }
+                />
+                <span>秒</span>
+              </div>
+            </div>
+          )}
+
           <div className="rest-countdown">
-            <p>距离下次休息还有</p>
+            <p>
+              {restIdle
+                ? "检测到离开，已计为休息，回来后重新计时"
+                : restPhase === "deferred"
+                  ? `${deferredReason ?? "演示中"}，结束后开始休息`
+                  : restPhase === "preWarning"
+                    ? "即将开始休息"
+                    : restPhase === "snoozed"
+                      ? "已推迟，距离休息还有"
+                      : "距离下次休息还有"}
+            </p>
             <h3>{nextRestCountdown}</h3>
           </div>
 
-          <button
-            className="btn btn--ghost"
-            type="button"
-            onClick={handleStartRest}
-          >
-            立即进入休息
-          </button>
+          <div className="setting-row">
+            <button
+              className="btn btn--ghost"
+              type="button"
+              onClick={handleStartRest}
+            >
+              立即进入休息
+            </button>
+            {restPhase === "preWarning" && snoozesLeft > 0 && (
+              <button
+                className="btn btn--soft"
+                type="button"
+                onClick={handleSnoozeRest}
+              >
+                稍后 {snoozeMinutes} 分钟（剩 {snoozesLeft} 次）
+              </button>
+            )}
+            {(restPhase === "preWarning" ||
+              restPhase === "snoozed" ||
+              restPhase === "deferred") && (
+              <button
+                className="btn btn--ghost"
+                type="button"
+                onClick={handleSkipRest}
+              >
+                跳过本次休息
+              </button>
+            )}
+          </div>
         </div>
 
         <div className="card">
@@ -740,6 +1265,263 @@ function App() {
               </label>
             </label>
 
+            <label className="setting-row">
+              <span>严格休息（锁屏不能随手关闭）</span>
+              <label className="toggle">
+                <input
+                  type="checkbox"
+                  checked={strictEnabled}
+                  onChange={() => setStrictEnabled((prev) => !prev)}
+                />
+                <span className="toggle__track" />
+              </label>
+            </label>
+
+            {strictEnabled && (
+              <>
+                <label className="setting-row">
+                  <span>每天可提前结束</span>
+                  <input
+                    className="text-input"
+                    type="number"
+                    min={0}
+                    max={20}
+                    value={dailyOverrides}
+                    onChange={(event) =>
+                      setDailyOverrides(Number(event.target.value))
+                    }
+                  />
+                </label>
+
+                <div className="setting-row">
+                  <span>
+                    {hasPassphrase ? "应急口令已设置" : "应急口令"}
+                  </span>
+                  <div className="strict-passphrase">
+                    <input
+                      className="text-input"
+                      type="password"
+                      placeholder="新口令"
+                      value={passphraseDraft}
+                      onChange={(event) =>
+                        setPassphraseDraft(event.target.value)
+                      }
+                    />
+                    <button
+                      className="btn btn--soft"
+                      type="button"
+                      disabled={!passphraseDraft.trim()}
+                      onClick={() => handleStrictPassphrase(passphraseDraft)}
+                    >
+                      保存
+                    </button>
+                    {hasPassphrase && (
+                      <button
+                        className="btn btn--ghost"
+                        type="button"
+                        onClick={() => handleStrictPassphrase(null)}
+                      >
+                        清除
+                      </button>
+                    )}
+                  </div>
+                </div>
+                {strictMessage && (
+                  <p className="helper-text">{strictMessage}</p>
+                )}
+              </>
+            )}
+
+            <label className="setting-row">
+              <span>休息预告（秒，0 为不预告）</span>
+              <input
+                className="text-input"
+                type="number"
+                min={0}
+                max={600}
+                step={10}
+                value={preWarningSeconds}
+                onChange={(event) =>
+                  setPreWarningSeconds(Number(event.target.value))
+                }
+              />
+            </label>
+
+            <label className="setting-row">
+              <span>全屏时推迟休息</span>
+              <label className="toggle">
+                <input
+                  type="checkbox"
+                  checked={deferFullscreen}
+                  onChange={() => setDeferFullscreen((prev) => !prev)}
+                />
+                <span className="toggle__track" />
+              </label>
+            </label>
+
+            <label className="setting-row">
+              <span>这些程序在前台时推迟休息</span>
+              <input
+                className="text-input"
+                type="text"
+                placeholder="zoom, obs, powerpnt"
+                value={deferApps}
+                onChange={(event) => setDeferApps(event.target.value)}
+              />
+            </label>
+
+            <label className="setting-row">
+              <span>每次休息最多推迟</span>
+              <input
+                className="text-input"
+                type="number"
+                min={0}
+                max={10}
+                value={maxSnoozes}
+                onChange={(event) => setMaxSnoozes(Number(event.target.value))}
+              />
+            </label>
+
+            <label className="setting-row">
+              <span>锁屏边缘外扩（像素）</span>
+              <input
+                className="text-input"
+                type="number"
+                min={0}
+                max={1000}
+                step={50}
+                value={lockOverscan}
+                onChange={(event) =>
+                  setLockOverscan(Number(event.target.value))
+                }
+              />
+            </label>
+
+            <div className="slider-group">
+              <div className="slider-row">
+                <span>色温渐变</span>
+                <span>{(transitionMs / 1000).toFixed(1)} 秒</span>
+              </div>
+              <input
+                type="range"
+                min={0}
+                max={3000}
+                step={100}
+                value={transitionMs}
+                onChange={(event) => setTransitionMs(Number(event.target.value))}
+              />
+            </div>
+
+            <div className="slider-group">
+              <div className="slider-row">
+                <span>色温算法</span>
+              </div>
+              <div className="chips">
+                {colorAlgorithms.map((item) => (
+                  <button
+                    key={item.value}
+                    type="button"
+                    className={`chip ${
+                      colorAlgorithm === item.value ? "chip--active" : ""
+                    }`}
+                    onClick={() => setColorAlgorithm(item.value)}
+                  >
+                    {item.label}
+                  </button>
+                ))}
+              </div>
+            </div>
+
+            {solarLocation && (
+              <>
+                <label className="setting-row">
+                  <span>纬度（智能预设）</span>
+                  <input
+                    className="text-input"
+                    type="number"
+                    min={-90}
+                    max={90}
+                    step={0.1}
+                    value={solarLocation.latitude}
+                    onChange={(event) => {
+                      const latitude = Number(event.target.value);
+                      if (Number.isNaN(latitude)) return;
+                      setSolarLocation((prev) =>
+                        prev ? { ...prev, latitude } : prev,
+                      );
+                    }}
+                  />
+                </label>
+                <label className="setting-row">
+                  <span>经度（智能预设）</span>
+                  <input
+                    className="text-input"
+                    type="number"
+                    min={-180}
+                    max={180}
+                    step={0.1}
+                    value={solarLocation.longitude}
+                    onChange={(event) => {
+                      const longitude = Number(event.target.value);
+                      if (Number.isNaN(longitude)) return;
+                      setSolarLocation((prev) =>
+                        prev ? { ...prev, longitude } : prev,
+                      );
+                    }}
+                  />
+                </label>
+              </>
+            )}
+
+            <div className="slider-group">
+              <div className="slider-row">
+                <span>配置文件（.json / .toml）</span>
+              </div>
+              <input
+                className="text-input"
+                type="text"
+                placeholder="例如 D:\huyanba.toml"
+                value={profilePath}
+                onChange={(event) => setProfilePath(event.target.value)}
+              />
+              <div className="setting-row">
+                <button
+                  className="btn btn--soft"
+                  type="button"
+                  disabled={!profilePath.trim()}
+                  onClick={() =>
+                    runProfileCommand("export_settings", {
+                      path: profilePath.trim(),
+                    })
+                  }
+                >
+                  导出
+                </button>
+                <button
+                  className="btn btn--soft"
+                  type="button"
+                  disabled={!profilePath.trim()}
+                  onClick={() =>
+                    runProfileCommand("import_settings", {
+                      path: profilePath.trim(),
+                    })
+                  }
+                >
+                  导入
+                </button>
+                <button
+                  className="btn btn--ghost"
+                  type="button"
+                  onClick={() => runProfileCommand("reset_settings", {})}
+                >
+                  恢复默认
+                </button>
+              </div>
+              {profileMessage && (
+                <p className="helper-text profile-message">{profileMessage}</p>
+              )}
+            </div>
+
             <label className="setting-row">
               <span>开机自启</span>
               <label className="toggle">
@@ -749,6 +1531,193 @@ function App() {
             </label>
           </div>
         </div>
+
+        <div className="card">
+          <div className="card__header">
+            <div>
+              <p className="card__eyebrow">多显示器</p>
+              <h2>单独调节</h2>
+            </div>
+          </div>
+
+          <div className="settings">
+            {displayOutputs.length === 0 && (
+              <p className="helper-text">未检测到可调节的显示器</p>
+            )}
+            {displayOutputs.map((output) => (
+              <div key={output.id} className="output-item">
+                <label className="setting-row">
+                  <span>
+                    {output.id}
+                    <span className="helper-text">
+                      {" "}
+                      {output.width}×{output.height}
+                    </span>
+                  </span>
+                  <label className="toggle">
+                    <input
+                      type="checkbox"
+                      checked={output.custom !== null}
+                      disabled={!output.gammaSupported}
+                      onChange={() =>
+                        updateOutputSetting(
+                          output.id,
+                          output.custom
+                            ? null
+                            : {
+                                filterEnabled,
+                                strength: filterStrength,
+                                colorTemp,
+                              },
+                        )
+                      }
+                    />
+                    <span className="toggle__track" />
+                  </label>
+                </label>
+                {output.custom && (
+                  <>
+                    <div className="slider-group">
+                      <div className="slider-row">
+                        <span>强度</span>
+                        <span>{output.custom.strength}%</span>
+                      </div>
+                      <input
+                        type="range"
+                        min={0}
+                        max={100}
+                        value={output.custom.strength}
+                        onChange={(event) =>
+                          updateOutputSetting(output.id, {
+                            ...output.custom!,
+                            filterEnabled: true,
+                            strength: Number(event.target.value),
+                          })
+                        }
+                      />
+                    </div>
+                    <div className="slider-group">
+                      <div className="slider-row">
+                        <span>色调</span>
+                        <span>{output.custom.colorTemp}K</span>
+                      </div>
+                      <input
+                        type="range"
+                        min={2000}
+                        max={6500}
+                        step={100}
+                        value={output.custom.colorTemp}
+                        onChange={(event) =>
+                          updateOutputSetting(output.id, {
+                            ...output.custom!,
+                            filterEnabled: true,
+                            colorTemp: Number(event.target.value),
+                          })
+                        }
+                      />
+                    </div>
+                  </>
+                )}
+              </div>
+            ))}
+          </div>
+        </div>
+
+        <div className="card">
+          <div className="card__header">
+            <div>
+              <p className="card__eyebrow">时间曲线</p>
+              <h2>自定义关键帧</h2>
+            </div>
+            <label className="toggle">
+              <input
+                type="checkbox"
+                checked={colorCurve?.enabled ?? false}
+                disabled={!colorCurve}
+                onChange={() => {
+                  const enabled = !(colorCurve?.enabled ?? false);
+                  runCurveCommand("set_color_curve_enabled", { enabled });
+                  if (enabled) {
+                    setActivePreset("时间曲线");
+                  }
+                }}
+              />
+              <span className="toggle__track" />
+            </label>
+          </div>
+
+          <div className="settings">
+            {colorCurve?.keyframes.map((keyframe) => (
+              <div key={keyframe.id} className="setting-row">
+                <input
+                  className="text-input"
+                  type="time"
+                  value={keyframe.time}
+                  onChange={(event) =>
+                    runCurveCommand("update_curve_keyframe", {
+                      id: keyframe.id,
+                      keyframe: { ...keyframe, time: event.target.value },
+                    })
+                  }
+                />
+                <input
+                  className="text-input"
+                  type="number"
+                  min={1000}
+                  max={10000}
+                  step={100}
+                  value={keyframe.colorTemp}
+                  onChange={(event) =>
+                    runCurveCommand("update_curve_keyframe", {
+                      id: keyframe.id,
+                      keyframe: {
+                        ...keyframe,
+                        colorTemp: Number(event.target.value),
+                      },
+                    })
+                  }
+                />
+                <input
+                  className="text-input"
+                  type="number"
+                  min={0}
+                  max={100}
+                  value={keyframe.strength}
+                  onChange={(event) =>
+                    runCurveCommand("update_curve_keyframe", {
+                      id: keyframe.id,
+                      keyframe: {
+                        ...keyframe,
+                        strength: Number(event.target.value),
+                      },
+                    })
+                  }
+                />
+                <button
+                  className="btn btn--ghost"
+                  type="button"
+                  onClick={() =>
+                    runCurveCommand("delete_curve_keyframe", { id: keyframe.id })
+                  }
+                >
+                  删除
+                </button>
+              </div>
+            ))}
+            {curveError && <p className="helper-text">{curveError}</p>}
+            <button
+              className="btn btn--soft"
+              type="button"
+              onClick={() =>
+                runCurveCommand("add_curve_keyframe", {
+                  keyframe: { time: "12:00", colorTemp, strength: filterStrength },
+                })
+              }
+            >
+              添加关键帧
+            </button>
+          </div>
+        </div>
           </section>
 
           <section className="preview-row">
@@ -801,7 +1770,7 @@ function App() {
             <button
               className="lockscreen__nav-btn"
               type="button"
-              onClick={handlePrevWallpaper}
+              onClick={() => runLockAction("prevWallpaper")}
               aria-label="上一张壁纸"
             >
               {"<"}
@@ -809,7 +1778,7 @@ function App() {
             <button
               className="lockscreen__nav-btn"
               type="button"
-              onClick={handleNextWallpaper}
+              onClick={() => runLockAction("nextWallpaper")}
               aria-label="下一张壁纸"
             >
               {">"}
@@ -818,8 +1787,8 @@ function App() {
           <div className="lockscreen__content">
             <div className="lockscreen__top">
               <div>
-                <p className="lockscreen__time">{lockPayload.timeText}</p>
-                <p className="lockscreen__date">{lockPayload.dateText}</p>
+                <p className="lockscreen__time">{lockTimeText}</p>
+                <p className="lockscreen__date">{lockDateText}</p>
               </div>
               <div />
             </div>
@@ -829,13 +1798,13 @@ function App() {
                 <p className="lockscreen__timer-label">剩余时间</p>
                 <div
                   className={`lockscreen__timer-value ${
-                    lockPayload.restPaused ? "is-paused" : ""
+                    lockPaused ? "is-paused" : ""
                   }`}
                 >
-                  {lockPayload.restCountdown.replaceAll(":", " : ")}
+                  {lockCountdown.replaceAll(":", " : ")}
                 </div>
                 <p className="lockscreen__timer-hint">
-                  {lockPayload.restPaused
+                  {lockPaused
                     ? "计时已暂停，点击继续恢复倒计时"
                     : "闭眼 20 秒，眺望远处 20 秒"}
                 </p>
@@ -845,10 +1814,60 @@ function App() {
               </p>
             </div>
             <div className="lockscreen__actions">
-              {lockPayload.allowEscExit ? (
-                <span className="helper-text">ESC 退出已开启</span>
+              {lockStrict?.enabled ? (
+                <form
+                  className="lockscreen__strict"
+                  onSubmit={(event) => {
+                    event.preventDefault();
+                    handleEmergencyExit(lockPassphrase);
+                    setLockPassphrase("");
+                  }}
+                >
+                  {lockStrict.hasPassphrase && (
+                    <input
+                      className="text-input"
+                      type="password"
+                      placeholder="应急口令"
+                      value={lockPassphrase}
+                      onChange={(event) =>
+                        setLockPassphrase(event.target.value)
+                      }
+                    />
+                  )}
+                  <button
+                    className="btn btn--ghost"
+                    type="button"
+                    disabled={lockStrict.overridesLeft === 0}
+                    onClick={() => handleEmergencyExit(null)}
+                  >
+                    提前结束（今天还剩 {lockStrict.overridesLeft} 次）
+                  </button>
+                  {lockExitError && (
+                    <span className="helper-text">{lockExitError}</span>
+                  )}
+                </form>
               ) : (
-                <span className="helper-text">ESC 已禁用</span>
+                <>
+                  {lockSnoozesLeft > 0 && (
+                    <button
+                      className="btn btn--ghost"
+                      type="button"
+                      onClick={() =>
+                        runLockAction({ snooze: lockSnoozeMs / 1000 })
+                      }
+                    >
+                      稍后 {Math.ceil(lockSnoozeMs / 60000)} 分钟
+                    </button>
+                  )}
+                  <span className="helper-text">
+                    {lockUpdate?.allowEscExit
+                      ? "ESC 退出已开启"
+                      : "ESC 已禁用"}
+                  </span>
+                  {lockExitError && (
+                    <span className="helper-text">{lockExitError}</span>
+                  )}
+                </>
               )}
             </div>
           </div>
@@ -0,0 +1,66 @@
+import { useEffect, useState } from "react";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+import { invoke } from "@tauri-apps/api/core";
+import "./App.css";
+
+type MicroSnapshot = {
+  microRemainingMs: number | null;
+};
+
+// 20-20-20 小憩小窗：倒计时以后台调度为准，结束时由后台关闭窗口
+function MicroBreak() {
+  const [endAt, setEndAt] = useState(() => {
+    const end = Number(
+      new URLSearchParams(window.location.search).get("end") || 0,
+    );
+    return end > 0 ? end : Date.now();
+  });
+  const [now, setNow] = useState(() => Date.now());
+
+  useEffect(() => {
+    const timer = window.setInterval(() => setNow(Date.now()), 250);
+    return () => window.clearInterval(timer);
+  }, []);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    getCurrentWebviewWindow()
+      .listen<MicroSnapshot>("rest-state", (event) => {
+        const remaining = event.payload.microRemainingMs;
+        if (remaining !== null) {
+          setEndAt(Date.now() + remaining);
+        }
+      })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((error) => console.error("监听小憩状态失败", error));
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
+  const handleDone = () => {
+    invoke("end_micro_break").catch((error) =>
+      console.error("结束小憩失败", error),
+    );
+  };
+
+  const seconds = Math.max(0, Math.ceil((endAt - now) / 1000));
+
+  return (
+    <div className="micro-break">
+      <div>
+        <p className="micro-break__title">看看 6 米外的地方</p>
+        <p className="micro-break__hint">让眼睛放松 {seconds} 秒</p>
+      </div>
+      <button className="btn btn--light" type="button" onClick={handleDone}>
+        好了
+      </button>
+    </div>
+  );
+}
+
+export default MicroBreak;
@@ -1,9 +1,14 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
 import App from "./App";
+import MicroBreak from "./MicroBreak";
+
+// 小憩小窗只需要倒计时，不加载主界面的各种副作用
+const isMicroBreak =
+  new URLSearchParams(window.location.search).get("microbreak") === "1";
 
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
-    <App />
+    {isMicroBreak ? <MicroBreak /> : <App />}
   </React.StrictMode>,
 );